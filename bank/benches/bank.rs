@@ -0,0 +1,65 @@
+use bank::account_number::{AccountNumber, NumberFormat};
+use bank::audit::{AuditLog, ChangeContext};
+use bank::Bank;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// Size of the tenant we need to support
+const ACCOUNTS: usize = 1_000_000;
+
+// No audit log: every iteration would hash and keep another record, so memory grows without
+// limit and the benches would mostly time SHA-256 instead of the index
+fn setup() -> (Bank, Vec<String>) {
+    let mut bank = Bank::with_number_format(NumberFormat::default(), AuditLog::disabled());
+    let ctx = ChangeContext::new("bench", "setup");
+    let numbers: Vec<AccountNumber> = (0..ACCOUNTS)
+        .map(|n| bank.open_account(&format!("holder {}", n), &ctx).unwrap())
+        .collect();
+
+    for number in &numbers {
+        bank.deposit(number.as_str(), 1_000, &ctx).unwrap();
+    }
+
+    // Lookups come in as text typed by users, so benchmark with the printed form
+    let numbers = numbers.iter().map(|number| number.to_string()).collect();
+    (bank, numbers)
+}
+
+fn bank_benchmarks(c: &mut Criterion) {
+    let (mut bank, numbers) = setup();
+    let ctx = ChangeContext::new("bench", "transfer");
+    // Large odd stride so consecutive iterations hit unrelated accounts
+    let mut next = 0;
+    let mut pick = move || {
+        next = (next + 7_919) % ACCOUNTS;
+        next
+    };
+
+    c.bench_function("lookup 1e6 accounts", |b| {
+        b.iter(|| bank.account(black_box(&numbers[pick()])).unwrap().balance())
+    });
+
+    c.bench_function("transfer 1e6 accounts", |b| {
+        b.iter(|| {
+            let from = pick();
+            let to = (from + 1) % ACCOUNTS;
+            // 1 back and forth keeps every balance positive however many iterations run
+            bank.transfer(&numbers[from], &numbers[to], 1, &ctx).unwrap();
+            bank.transfer(&numbers[to], &numbers[from], 1, &ctx).unwrap();
+        })
+    });
+
+    c.bench_function("total balance 1e6 accounts", |b| {
+        b.iter(|| black_box(&bank).total_balance())
+    });
+
+    c.bench_function("average balance 1e6 accounts", |b| {
+        b.iter(|| black_box(&bank).average_balance())
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = bank_benchmarks
+}
+criterion_main!(benches);
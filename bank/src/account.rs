@@ -0,0 +1,46 @@
+use super::account_number::AccountNumber;
+use super::bank::BankError;
+
+#[derive(Debug)]
+pub struct Account {
+    id: AccountNumber, // Issued by the bank, see NumberFormat
+    balance: i32,
+    holder: String,
+}
+
+impl Account {
+    pub(crate) fn new(id: AccountNumber, holder: String) -> Self {
+        Account {
+            id,
+            holder,
+            balance: 0,
+        }
+    }
+
+    pub fn id(&self) -> &AccountNumber {
+        &self.id
+    }
+
+    pub fn balance(&self) -> i32 {
+        self.balance
+    }
+
+    pub fn summary(&self) -> String {
+        format!("{} has a balance {}", self.holder, self.balance)
+    }
+
+    // Only the bank moves money so every change goes through the audit log
+    pub(crate) fn deposit(&mut self, amount: i32) -> Result<i32, BankError> {//Struct is being changed, use a mutable reference to self
+        self.balance = self.balance.checked_add(amount).ok_or_else(|| BankError::BalanceOverflow {
+            id: self.id.clone(),
+            balance: self.balance,
+            amount,
+        })?;
+        Ok(self.balance)
+    }
+
+    pub(crate) fn withdraw(&mut self, amount: i32) -> i32 {
+        self.balance -= amount;
+        self.balance
+    }
+}
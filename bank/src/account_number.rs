@@ -0,0 +1,204 @@
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Account numbers in the IBAN layout
+
+- Two letter country code, two check digits, then the BBAN (bank code + account serial)
+- Check digits: move the first four characters to the end, turn letters into numbers (A = 10 .. Z = 35),
+  the whole number mod 97 must be 1
+- Generated by the bank, validated every time a number comes back in from outside
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountNumber(String); // Stored without spaces e.g. GB29UDMY00000001
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountNumberError {
+    TooShort(usize),
+    InvalidCharacter(char),
+    InvalidCountryCode(String),
+    InvalidCheckDigits, // Typo somewhere in the number
+    WrongFormat { expected: String, found: String },
+    InvalidBankCode(String),
+    InvalidDigits(usize),
+    Exhausted { digits: usize },
+}
+
+impl fmt::Display for AccountNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountNumberError::TooShort(length) => {
+                write!(f, "account number is too short ({} characters)", length)
+            }
+            AccountNumberError::InvalidCharacter(c) => {
+                write!(f, "account number contains '{}', only letters and digits are allowed", c)
+            }
+            AccountNumberError::InvalidCountryCode(code) => {
+                write!(f, "'{}' is not a two letter country code", code)
+            }
+            AccountNumberError::InvalidCheckDigits => write!(
+                f,
+                "check digits don't match, the account number has probably been mistyped"
+            ),
+            AccountNumberError::WrongFormat { expected, found } => write!(
+                f,
+                "account number {} doesn't belong to this bank, expected the form {}",
+                found, expected
+            ),
+            AccountNumberError::InvalidBankCode(code) => {
+                write!(f, "bank code '{}' must be letters and digits", code)
+            }
+            AccountNumberError::InvalidDigits(digits) => {
+                write!(f, "account serials must be 1 to 18 digits, not {}", digits)
+            }
+            AccountNumberError::Exhausted { digits } => {
+                write!(f, "all {} digit account numbers have been issued", digits)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AccountNumberError {}
+
+impl AccountNumber {
+    pub fn country(&self) -> &str {
+        &self.0[..2]
+    }
+
+    pub fn bban(&self) -> &str {
+        &self.0[4..]
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// Printed in groups of four like an IBAN: GB29 UDMY 0000 0001
+impl fmt::Display for AccountNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let groups: Vec<&str> = self
+            .0
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect();
+        write!(f, "{}", groups.join(" "))
+    }
+}
+
+impl FromStr for AccountNumber {
+    type Err = AccountNumberError;
+
+    // Accepts spaces and lower case, whatever a person is likely to type
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let compact: String = text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        if let Some(bad) = compact.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(AccountNumberError::InvalidCharacter(bad));
+        }
+        if compact.len() < 5 {
+            return Err(AccountNumberError::TooShort(compact.len()));
+        }
+        if !compact[..2].chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AccountNumberError::InvalidCountryCode(compact[..2].to_string()));
+        }
+        if !compact[2..4].chars().all(|c| c.is_ascii_digit()) || mod97(&rearrange(&compact)) != 1 {
+            return Err(AccountNumberError::InvalidCheckDigits);
+        }
+
+        Ok(AccountNumber(compact))
+    }
+}
+
+fn rearrange(number: &str) -> String {
+    format!("{}{}", &number[4..], &number[..4])
+}
+
+// Works one character at a time so the number never has to fit in an integer
+fn mod97(text: &str) -> u32 {
+    text.chars().fold(0, |remainder, c| {
+        let value = c.to_digit(36).unwrap();
+        if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        }
+    })
+}
+
+/// How this bank lays out its account numbers
+#[derive(Debug, Clone)]
+pub struct NumberFormat {
+    country: String,
+    bank_code: String,
+    digits: usize,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            country: String::from("GB"),
+            bank_code: String::from("UDMY"),
+            digits: 8,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn new(country: &str, bank_code: &str, digits: usize) -> Result<Self, AccountNumberError> {
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AccountNumberError::InvalidCountryCode(country.to_string()));
+        }
+        if bank_code.is_empty() || !bank_code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AccountNumberError::InvalidBankCode(bank_code.to_string()));
+        }
+        // 18 digits is the most a u64 serial always fills
+        if !(1..=18).contains(&digits) {
+            return Err(AccountNumberError::InvalidDigits(digits));
+        }
+
+        Ok(NumberFormat {
+            country: country.to_ascii_uppercase(),
+            bank_code: bank_code.to_ascii_uppercase(),
+            digits,
+        })
+    }
+
+    /// Builds the account number for the given serial, filling in the check digits
+    pub fn generate(&self, serial: u64) -> Result<AccountNumber, AccountNumberError> {
+        let serial = format!("{:0width$}", serial, width = self.digits);
+        if serial.len() > self.digits {
+            return Err(AccountNumberError::Exhausted {
+                digits: self.digits,
+            });
+        }
+
+        let bban = format!("{}{}", self.bank_code, serial);
+        let check = 98 - mod97(&format!("{}{}00", bban, self.country));
+        Ok(AccountNumber(format!("{}{:02}{}", self.country, check, bban)))
+    }
+
+    /// Parses a number typed by a user and checks it was issued under this format
+    pub fn parse(&self, text: &str) -> Result<AccountNumber, AccountNumberError> {
+        let number: AccountNumber = text.parse()?;
+        let bban = number.bban();
+
+        let matches = number.country() == self.country
+            && bban.len() == self.bank_code.len() + self.digits
+            && bban.starts_with(&self.bank_code)
+            && bban[self.bank_code.len()..].chars().all(|c| c.is_ascii_digit());
+        if matches {
+            Ok(number)
+        } else {
+            Err(AccountNumberError::WrongFormat {
+                expected: format!("{}kk{}{}", self.country, self.bank_code, "n".repeat(self.digits)),
+                found: number.to_string(),
+            })
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/*
+Audit trail for the bank
+
+- Every record stores the hash of the record before it, so the records form a chain
+- Editing or deleting a record changes its hash and breaks the link to the next record
+- The first record points at GENESIS_HASH
+- On disk a record is one line of tab separated fields
+- Nothing comes after the last record to break, so the log's head (record count and last hash)
+  is kept in a second file, audit.log.head next to audit.log. Records cut off the end no longer
+  match it. A head with records but no log beside it means the log was deleted, and won't open.
+  Deleting both files together still starts a fresh, empty log
+- A record that can't be written along with its head is taken back off the log, so the log
+  only ever holds changes the bank made
+*/
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Who is making a change and why. Required by every bank mutation.
+#[derive(Debug, Clone)]
+pub struct ChangeContext {
+    pub actor: String,
+    pub reason: String,
+}
+
+impl ChangeContext {
+    pub fn new(actor: &str, reason: &str) -> Self {
+        ChangeContext {
+            actor: actor.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub actor: String,
+    pub reason: String,
+    pub action: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    fn new(seq: u64, timestamp: u64, ctx: &ChangeContext, action: &str, prev_hash: &str) -> Self {
+        let mut record = AuditRecord {
+            seq,
+            timestamp,
+            actor: clean_field(&ctx.actor),
+            reason: clean_field(&ctx.reason),
+            action: clean_field(action),
+            prev_hash: prev_hash.to_string(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        record
+    }
+
+    pub fn compute_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                self.seq, self.timestamp, self.actor, self.reason, self.action, self.prev_hash
+            )
+            .as_bytes(),
+        );
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    pub fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.seq, self.timestamp, self.actor, self.reason, self.action, self.prev_hash, self.hash
+        )
+    }
+
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            return Err(format!("expected 7 fields, found {}", fields.len()));
+        }
+
+        Ok(AuditRecord {
+            seq: fields[0].parse().map_err(|_| format!("bad sequence number '{}'", fields[0]))?,
+            timestamp: fields[1].parse().map_err(|_| format!("bad timestamp '{}'", fields[1]))?,
+            actor: fields[2].to_string(),
+            reason: fields[3].to_string(),
+            action: fields[4].to_string(),
+            prev_hash: fields[5].to_string(),
+            hash: fields[6].to_string(),
+        })
+    }
+}
+
+// Tabs and newlines are the file's separators so they can't appear inside a field
+fn clean_field(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+#[derive(Debug)]
+pub enum ChainError {
+    Io(io::Error),
+    Malformed { line: usize, reason: String },
+    OutOfSequence { line: usize, expected: u64, found: u64 },
+    BrokenLink { seq: u64, expected: String, found: String },
+    TamperedRecord { seq: u64 },
+    MissingHead,
+    MissingLog { expected: u64 },
+    BadHead(String),
+    CountMismatch { expected: u64, found: u64 },
+    HeadMismatch { expected: String, found: String },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChainError::Io(err) => write!(f, "could not read the audit log: {}", err),
+            ChainError::Malformed { line, reason } => {
+                write!(f, "line {} is not an audit record: {}", line, reason)
+            }
+            ChainError::OutOfSequence { line, expected, found } => write!(
+                f,
+                "line {} has sequence number {}, expected {} (record missing or reordered)",
+                line, found, expected
+            ),
+            ChainError::BrokenLink { seq, expected, found } => write!(
+                f,
+                "record {} points at previous hash {} but the previous record hashes to {}",
+                seq, found, expected
+            ),
+            ChainError::TamperedRecord { seq } => {
+                write!(f, "record {} does not match its own hash", seq)
+            }
+            ChainError::MissingHead => {
+                write!(f, "the log has records but no head file, its end can't be checked")
+            }
+            ChainError::MissingLog { expected } => {
+                write!(f, "the head expects {} records but the log file is gone", expected)
+            }
+            ChainError::BadHead(reason) => write!(f, "the head file is not readable: {}", reason),
+            ChainError::CountMismatch { expected, found } => write!(
+                f,
+                "expected {} records, the log has {} (records cut off the end or added behind the head)",
+                expected, found
+            ),
+            ChainError::HeadMismatch { expected, found } => write!(
+                f,
+                "the head points at hash {} but the last record hashes to {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+impl From<io::Error> for ChainError {
+    fn from(err: io::Error) -> Self {
+        ChainError::Io(err)
+    }
+}
+
+/// What the chain ends with: how many records and the last one's hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct Head {
+    pub count: u64,
+    pub hash: String,
+}
+
+impl Head {
+    pub fn of(records: &[AuditRecord]) -> Self {
+        Head {
+            count: records.len() as u64,
+            hash: records
+                .last()
+                .map(|record| record.hash.clone())
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        format!("{}\t{}", self.count, self.hash)
+    }
+
+    pub fn from_line(line: &str) -> Result<Self, ChainError> {
+        let (count, hash) = line
+            .trim_end()
+            .split_once('\t')
+            .ok_or_else(|| ChainError::BadHead(format!("expected count and hash, found '{}'", line.trim_end())))?;
+        Ok(Head {
+            count: count
+                .parse()
+                .map_err(|_| ChainError::BadHead(format!("bad record count '{}'", count)))?,
+            hash: hash.to_string(),
+        })
+    }
+
+    /// Checks the records end where the head says they do
+    pub fn check(&self, records: &[AuditRecord]) -> Result<(), ChainError> {
+        let actual = Head::of(records);
+        if actual.count != self.count {
+            return Err(ChainError::CountMismatch {
+                expected: self.count,
+                found: actual.count,
+            });
+        }
+        if actual.hash != self.hash {
+            return Err(ChainError::HeadMismatch {
+                expected: self.hash.clone(),
+                found: actual.hash,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// audit.log -> audit.log.head
+pub fn head_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".head");
+    PathBuf::from(name)
+}
+
+fn read_head(path: &Path) -> Result<Option<Head>, ChainError> {
+    match fs::read_to_string(head_path(path)) {
+        Ok(text) => Head::from_line(&text).map(Some),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(ChainError::Io(err)),
+    }
+}
+
+// Written beside the real head and renamed over it, so a crash leaves the old head or the new one
+fn write_head(path: &Path, head: &Head) -> io::Result<()> {
+    let head_file = head_path(path);
+    let mut temporary = head_file.clone().into_os_string();
+    temporary.push(".tmp");
+    fs::write(&temporary, format!("{}\n", head.to_line()))?;
+    fs::rename(&temporary, &head_file)
+}
+
+/// Walks the chain from the start and returns the first broken link
+pub fn verify_chain(records: &[AuditRecord]) -> Result<(), ChainError> {
+    check_links(records.iter().enumerate().map(|(index, record)| (index + 1, record)))
+}
+
+// Lines are the record's line in the file, blank lines included
+fn check_links<'a>(records: impl Iterator<Item = (usize, &'a AuditRecord)>) -> Result<(), ChainError> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+
+    for (expected_seq, (line, record)) in records.enumerate() {
+        let expected_seq = expected_seq as u64;
+        if record.seq != expected_seq {
+            return Err(ChainError::OutOfSequence {
+                line,
+                expected: expected_seq,
+                found: record.seq,
+            });
+        }
+        if record.prev_hash != prev_hash {
+            return Err(ChainError::BrokenLink {
+                seq: record.seq,
+                expected: prev_hash,
+                found: record.prev_hash.clone(),
+            });
+        }
+        if record.compute_hash() != record.hash {
+            return Err(ChainError::TamperedRecord { seq: record.seq });
+        }
+        prev_hash = record.hash.clone();
+    }
+
+    Ok(())
+}
+
+/// Reads an audit file written by `AuditLog`, checks every link and that it ends at its head
+pub fn verify_file(path: &Path) -> Result<usize, ChainError> {
+    let text = fs::read_to_string(path)?;
+    let records = check_file(path, &text)?;
+    Ok(records.len())
+}
+
+fn check_file(path: &Path, text: &str) -> Result<Vec<AuditRecord>, ChainError> {
+    let numbered = parse_records(text)?;
+    check_links(numbered.iter().map(|(line, record)| (*line, record)))?;
+    let records: Vec<AuditRecord> = numbered.into_iter().map(|(_, record)| record).collect();
+    match read_head(path)? {
+        Some(head) => head.check(&records)?,
+        None if records.is_empty() => {}
+        None => return Err(ChainError::MissingHead),
+    }
+    Ok(records)
+}
+
+fn parse_records(text: &str) -> Result<Vec<(usize, AuditRecord)>, ChainError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            AuditRecord::from_line(line)
+                .map(|record| (index + 1, record))
+                .map_err(|reason| ChainError::Malformed {
+                    line: index + 1,
+                    reason,
+                })
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    records: Vec<AuditRecord>,
+    file: Option<File>,
+    path: Option<PathBuf>,
+    disabled: bool,
+}
+
+impl AuditLog {
+    /// In-memory log, nothing written to disk
+    pub fn new() -> Self {
+        AuditLog::default()
+    }
+
+    /// Keeps no records at all. Only for benchmarks that measure the bank itself, not the hashing
+    pub fn disabled() -> Self {
+        AuditLog {
+            disabled: true,
+            ..AuditLog::default()
+        }
+    }
+
+    /// Continues the chain in an existing file (or starts a new one).
+    /// Refuses to append to a file whose chain is already broken or doesn't reach its head.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let records = match fs::read_to_string(path) {
+            Ok(text) => check_file(path, &text).map_err(io::Error::other)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => match read_head(path).map_err(io::Error::other)? {
+                Some(head) if head.count > 0 => {
+                    return Err(io::Error::other(ChainError::MissingLog { expected: head.count }))
+                }
+                _ => vec![],
+            },
+            Err(err) => return Err(err),
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        write_head(path, &Head::of(&records))?;
+        Ok(AuditLog {
+            records,
+            file: Some(file),
+            path: Some(path.to_path_buf()),
+            disabled: false,
+        })
+    }
+
+    pub fn records(&self) -> &[AuditRecord] {
+        &self.records
+    }
+
+    /// Checks the records in memory and, for a log kept in a file, that the file still holds all of them
+    pub fn verify(&self) -> Result<(), ChainError> {
+        verify_chain(&self.records)?;
+        if let Some(path) = &self.path {
+            let found = verify_file(path)?;
+            if found != self.records.len() {
+                return Err(ChainError::CountMismatch {
+                    expected: self.records.len() as u64,
+                    found: found as u64,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends a record. The record only joins the chain once it and the head are both written,
+    /// on an error the file is cut back to where it was so it matches the records in memory
+    pub fn append(&mut self, ctx: &ChangeContext, action: &str) -> io::Result<()> {
+        if self.disabled {
+            return Ok(());
+        }
+        let prev_hash = self
+            .records
+            .last()
+            .map(|record| record.hash.as_str())
+            .unwrap_or(GENESIS_HASH);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        let record = AuditRecord::new(self.records.len() as u64, timestamp, ctx, action, prev_hash);
+
+        if let Some(file) = self.file.as_mut() {
+            let length = file.metadata()?.len();
+            let head = Head {
+                count: record.seq + 1,
+                hash: record.hash.clone(),
+            };
+            let written = writeln!(file, "{}", record.to_line()).and_then(|()| match &self.path {
+                Some(path) => write_head(path, &head),
+                None => Ok(()),
+            });
+            if let Err(reason_this_failed) = written {
+                // The head still holds the old count. If this fails too, verify() reports the extra record
+                let _ = file.set_len(length);
+                return Err(reason_this_failed);
+            }
+        }
+
+        self.records.push(record);
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use super::account::Account;
+use super::account_number::{AccountNumber, AccountNumberError, NumberFormat};
+use super::audit::{AuditLog, ChangeContext};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum BankError {
+    InvalidAccountNumber { input: String, reason: AccountNumberError },
+    AccountNotFound(AccountNumber),
+    NumberingFailed(AccountNumberError),
+    InvalidAmount(i32),
+    InsufficientFunds { id: AccountNumber, balance: i32, requested: i32 },
+    BalanceOverflow { id: AccountNumber, balance: i32, amount: i32 },
+    Audit(io::Error),
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BankError::InvalidAccountNumber { input, reason } => {
+                write!(f, "'{}' is not a valid account number: {}", input, reason)
+            }
+            BankError::AccountNotFound(id) => write!(f, "no account with number {}", id),
+            BankError::NumberingFailed(reason) => write!(f, "can't open an account: {}", reason),
+            BankError::InvalidAmount(amount) => {
+                write!(f, "amount must be positive, got {}", amount)
+            }
+            BankError::InsufficientFunds { id, balance, requested } => write!(
+                f,
+                "account {} has a balance of {}, can't take {}",
+                id, balance, requested
+            ),
+            BankError::BalanceOverflow { id, balance, amount } => write!(
+                f,
+                "account {} has a balance of {}, adding {} would overflow it",
+                id, balance, amount
+            ),
+            BankError::Audit(err) => write!(f, "could not write the audit log: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BankError {}
+
+impl From<io::Error> for BankError {
+    fn from(err: io::Error) -> Self {
+        BankError::Audit(err)
+    }
+}
+
+/**
+Storage
+- Accounts stay in a Vec so summaries come out in the order accounts were opened
+- `index` maps an account number to its position in the Vec, lookups don't scan
+- `total` is kept up to date by every mutation so aggregates don't scan either
+*/
+#[derive(Debug)]
+pub struct Bank {
+    accounts: Vec<Account>,
+    index: HashMap<AccountNumber, usize>,
+    total: i64, // Sum of many i32 balances can overflow an i32
+    audit: AuditLog,
+    number_format: NumberFormat,
+    next_serial: u64,
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Bank::new()
+    }
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        Bank::with_number_format(NumberFormat::default(), AuditLog::new())
+    }
+
+    pub fn with_number_format(number_format: NumberFormat, audit: AuditLog) -> Self {
+        Bank {
+            accounts: vec![],
+            index: HashMap::new(),
+            total: 0,
+            audit,
+            number_format,
+            next_serial: 1,
+        }
+    }
+
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    // Mutations below write the audit record first so a failed write leaves the bank untouched
+
+    /// Opens an account under the next free account number and returns the number
+    pub fn open_account(&mut self, holder: &str, ctx: &ChangeContext) -> Result<AccountNumber, BankError> {
+        let id = self
+            .number_format
+            .generate(self.next_serial)
+            .map_err(BankError::NumberingFailed)?;
+
+        self.audit
+            .append(ctx, &format!("open account {} for {}", id, holder))?;
+        self.index.insert(id.clone(), self.accounts.len());
+        self.accounts.push(Account::new(id.clone(), holder.to_string()));
+        self.next_serial += 1;
+        Ok(id)
+    }
+
+    pub fn deposit(&mut self, number: &str, amount: i32, ctx: &ChangeContext) -> Result<i32, BankError> {
+        check_amount(amount)?;
+        let index = self.find(number)?;
+        check_room(&self.accounts[index], amount)?;
+
+        self.audit.append(
+            ctx,
+            &format!("deposit {} into {}", amount, self.accounts[index].id()),
+        )?;
+        self.total += amount as i64;
+        self.accounts[index].deposit(amount)
+    }
+
+    pub fn withdraw(&mut self, number: &str, amount: i32, ctx: &ChangeContext) -> Result<i32, BankError> {
+        check_amount(amount)?;
+        let index = self.find(number)?;
+        check_funds(&self.accounts[index], amount)?;
+
+        self.audit.append(
+            ctx,
+            &format!("withdraw {} from {}", amount, self.accounts[index].id()),
+        )?;
+        self.total -= amount as i64;
+        Ok(self.accounts[index].withdraw(amount))
+    }
+
+    pub fn transfer(
+        &mut self,
+        from: &str,
+        to: &str,
+        amount: i32,
+        ctx: &ChangeContext,
+    ) -> Result<(), BankError> {
+        check_amount(amount)?;
+        let from = self.find(from)?;
+        let to = self.find(to)?;
+        check_funds(&self.accounts[from], amount)?;
+        check_room(&self.accounts[to], amount)?;
+
+        self.audit.append(
+            ctx,
+            &format!(
+                "transfer {} from {} to {}",
+                amount,
+                self.accounts[from].id(),
+                self.accounts[to].id()
+            ),
+        )?;
+        self.accounts[from].withdraw(amount);
+        self.accounts[to].deposit(amount)?;
+        Ok(())
+    }
+
+    /// Looks an account up by a number typed by a user, rejecting typos before searching
+    pub fn account(&self, number: &str) -> Result<&Account, BankError> {
+        self.find(number).map(|index| &self.accounts[index])
+    }
+
+    fn find(&self, number: &str) -> Result<usize, BankError> {
+        let id = self
+            .number_format
+            .parse(number)
+            .map_err(|reason| BankError::InvalidAccountNumber {
+                input: number.to_string(),
+                reason,
+            })?;
+
+        self.index
+            .get(&id)
+            .copied()
+            .ok_or(BankError::AccountNotFound(id))
+    }
+
+    pub fn total_balance(&self) -> i64 {
+        self.total
+    }
+
+    pub fn account_count(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn average_balance(&self) -> f64 {
+        if self.accounts.is_empty() {
+            0.0
+        } else {
+            self.total as f64 / self.accounts.len() as f64
+        }
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        self.accounts
+            .iter()
+            .map(|account| account.summary())
+            .collect::<Vec<String>>()
+    }
+}
+
+fn check_amount(amount: i32) -> Result<(), BankError> {
+    if amount > 0 {
+        Ok(())
+    } else {
+        Err(BankError::InvalidAmount(amount))
+    }
+}
+
+fn check_funds(account: &Account, amount: i32) -> Result<(), BankError> {
+    if account.balance() >= amount {
+        Ok(())
+    } else {
+        Err(BankError::InsufficientFunds {
+            id: account.id().clone(),
+            balance: account.balance(),
+            requested: amount,
+        })
+    }
+}
+
+// Checked before the audit record is written, Account::deposit checks again when it adds
+fn check_room(account: &Account, amount: i32) -> Result<(), BankError> {
+    match account.balance().checked_add(amount) {
+        Some(..) => Ok(()),
+        None => Err(BankError::BalanceOverflow {
+            id: account.id().clone(),
+            balance: account.balance(),
+            amount,
+        }),
+    }
+}
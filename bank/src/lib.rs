@@ -0,0 +1,6 @@
+pub mod account;
+pub mod account_number;
+pub mod audit;
+mod bank;
+
+pub use bank::{Bank, BankError};
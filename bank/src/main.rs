@@ -20,62 +20,15 @@ Thought process with Rust
 
  */
 
-#[derive(Debug)]
-struct Account {
-    id: u32,
-    balance: i32,
-    holder: String,
-}
-
-impl Account {
-    fn new(id: u32, holder: String) -> Self {
-        Account {
-            id,
-            holder,
-            balance: 0,
-        }
-    }
-
-    fn summary(&self) -> String {
-        format!("{} has a balance {}", self.holder, self.balance)
-    }
-
-    fn deposit(&mut self, amount: i32) -> i32 {//Struct is being changed, use a mutable reference to self
-        self.balance += amount;
-        self.balance
-    }
+use bank::account::Account;
+use bank::account_number::NumberFormat;
+use bank::audit::{self, AuditLog, ChangeContext};
+use bank::Bank;
+use std::env;
+use std::path::Path;
+use std::process;
 
-    fn withdraw(&mut self, amount: i32) -> i32 {
-        self.balance -= amount;
-        self.balance
-    }
-}
-
-#[derive(Debug)]
-struct Bank {
-    accounts: Vec<Account>,
-}
-
-impl Bank {
-    fn new() -> Self {
-        Bank { accounts: vec![] }
-    }
-
-    fn add_account(&mut self, account: Account) {
-        self.accounts.push(account);
-    }
-
-    fn total_balance(&self) -> i32 {
-        self.accounts.iter().map(|account| account.balance).sum()
-    }
-
-    fn summary(&self) -> Vec<String> {
-        self.accounts
-            .iter()
-            .map(|account| account.summary())
-            .collect::<Vec<String>>()
-    }
-}
+const AUDIT_LOG: &str = "bank/audit.log";
 
 // fn print_account(account: Account) {
 //     println!("{:#?}", account);
@@ -86,22 +39,66 @@ fn print_account(account: &Account) {
     println!("{:#?}", account);
 }
 
+// cargo run -p bank -- verify [path] walks the audit chain and reports the first broken link
+fn verify(path: &str) {
+    match audit::verify_file(Path::new(path)) {
+        Ok(count) => println!("{}: {} records, chain intact", path, count),
+        Err(first_broken_link) => {
+            println!("{}: {}", path, first_broken_link);
+            process::exit(1);
+        }
+    }
+}
+
 fn main() {
-    let mut bank = Bank::new();
-    let mut account = Account::new(1, String::from("me"));
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("verify") {
+        verify(args.get(2).map(String::as_str).unwrap_or(AUDIT_LOG));
+        return;
+    }
+
+    let number_format = NumberFormat::new("GB", "UDMY", 8).expect("bad account number format");
+    let audit_log = match AuditLog::open(Path::new(AUDIT_LOG)) {
+        Ok(audit_log) => audit_log,
+        Err(why_this_failed) => {
+            println!("Keeping the audit log in memory: {}", why_this_failed);
+            AuditLog::new()
+        }
+    };
+    let mut bank = Bank::with_number_format(number_format, audit_log);
+    let teller = ChangeContext::new("teller-1", "walk-in customer");
     // let mut account = Account::new(1, String::from("me"));
     // let other_bank = bank; <---- moving the bank variable occurs here
     // let list_of_accounts = vec![account]; <--- passing ownership to of the account to the list_of_accounts variable
     println!("{:#?}", bank);
-    let account_ref = &account; //Reference to a value
-                                // println!("{:#?}", bank);
-    // bank.add_account(account); TODO - change this to avoid error with lines below. Use ref?
-account.deposit(1234);
-    account.withdraw(1233);
+    let mine = bank.open_account("me", &teller).expect("failed to open account"); // The bank owns the account, we only get its number back
+    let yours = bank.open_account("you", &teller).expect("failed to open account");
+    println!("Opened {} and {}", mine, yours);
+    bank.deposit(mine.as_str(), 1234, &ChangeContext::new("teller-1", "cash deposit"))
+        .expect("deposit failed");
+    bank.withdraw(mine.as_str(), 1233, &ChangeContext::new("atm-7", "cash withdrawal"))
+        .expect("withdrawal failed");
+    if let Err(reason_this_failed) = bank.transfer(
+        &mine.to_string(),
+        &yours.to_string(),
+        5,
+        &ChangeContext::new("me", "rent"),
+    ) {
+        println!("{}", reason_this_failed);
+    }
+    let typo = mine.to_string().replace("0001", "0010");
+    if let Err(reason_this_failed) = bank.account(&typo) {
+        println!("{}", reason_this_failed);
+    }
+    let account_ref = bank.account(mine.as_str()).unwrap(); //Reference to a value
     print_account(account_ref);
     print_account(account_ref);
     println!("{:#?}", bank.summary());
     println!("Total balance: {}", bank.total_balance());
+    match bank.audit_log().verify() {
+        Ok(..) => println!("Audit records: {}", bank.audit_log().records().len()),
+        Err(first_broken_link) => println!("Audit log is broken: {}", first_broken_link),
+    }
     // print_account(account); <--- error - use of moved value account
 
     //print_holder(account.holder);///moving the holder property out
@@ -0,0 +1,51 @@
+use bank::account_number::NumberFormat;
+use bank::audit::{self, AuditLog, ChangeContext};
+use bank::Bank;
+use std::fs;
+use std::path::PathBuf;
+
+// A fresh directory per test, so tests running at once don't share files
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bank-audit-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn a_deleted_log_with_its_head_left_does_not_open() {
+    let dir = scratch("deleted");
+    let path = dir.join("audit.log");
+    let mut log = AuditLog::open(&path).unwrap();
+    log.append(&ChangeContext::new("test", "one"), "first").unwrap();
+    drop(log);
+
+    fs::remove_file(&path).unwrap();
+    assert!(AuditLog::open(&path).is_err());
+    // The head is still there to catch it next time
+    assert!(audit::head_path(&path).exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_head_that_cannot_be_written_leaves_log_and_bank_unchanged() {
+    let dir = scratch("head");
+    let path = dir.join("audit.log");
+    let mut bank = Bank::with_number_format(NumberFormat::default(), AuditLog::open(&path).unwrap());
+    let ctx = ChangeContext::new("test", "setup");
+    let number = bank.open_account("Ann", &ctx).unwrap();
+
+    // A directory where the head's temporary file goes makes writing the head fail
+    let mut blocker = audit::head_path(&path).into_os_string();
+    blocker.push(".tmp");
+    fs::create_dir(&blocker).unwrap();
+    assert!(bank.deposit(number.as_str(), 50, &ctx).is_err());
+    assert_eq!(bank.account(number.as_str()).unwrap().balance(), 0);
+    assert_eq!(bank.audit_log().records().len(), 1);
+    bank.audit_log().verify().unwrap();
+
+    fs::remove_dir(&blocker).unwrap();
+    bank.deposit(number.as_str(), 50, &ctx).unwrap();
+    assert_eq!(audit::verify_file(&path).unwrap(), 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
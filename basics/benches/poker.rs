@@ -0,0 +1,42 @@
+use basics::card::Card;
+use basics::deck::Deck;
+use basics::poker;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// Same hands every run so results are comparable
+fn hands(size: usize, count: usize) -> Vec<Vec<Card>> {
+    (0..count as u64)
+        .map(|seed| {
+            let mut deck = Deck::with_seed(seed);
+            deck.shuffle();
+            deck.deal(size).unwrap()
+        })
+        .collect()
+}
+
+fn poker_benchmarks(c: &mut Criterion) {
+    let fives: Vec<[Card; 5]> = hands(5, 1_000)
+        .into_iter()
+        .map(|hand| hand.try_into().unwrap())
+        .collect();
+    let sevens = hands(7, 1_000);
+
+    c.bench_function("evaluate 1000 five card hands", |b| {
+        b.iter(|| {
+            for hand in &fives {
+                black_box(poker::evaluate_5(black_box(hand)));
+            }
+        })
+    });
+
+    c.bench_function("best of 1000 seven card hands", |b| {
+        b.iter(|| {
+            for hand in &sevens {
+                black_box(poker::best_of(black_box(hand)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, poker_benchmarks);
+criterion_main!(benches);
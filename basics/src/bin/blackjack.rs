@@ -0,0 +1,155 @@
+use basics::blackjack::{Game, Hand, Outcome, PlayerAction, RoundResult, Rules};
+use basics::cli::{self, flag};
+use basics::deck::Deck;
+use basics::render::{Renderer, Style};
+use std::env;
+use std::io::{self, BufRead, Write};
+
+/*
+Terminal Blackjack
+- cargo run -p basics --bin blackjack [--h17] [--seed N] [--cards STYLE]
+- --h17 makes the dealer hit soft 17, --seed replays the same shuffle
+- --cards is text, codes (the default), unicode, color, ascii or ascii-color
+ */
+const STARTING_CHIPS: i64 = 1_000;
+
+// Multi-line styles go under the label, the rest after it
+fn show(renderer: &dyn Renderer, label: &str, drawn: String) {
+    if renderer.multiline() {
+        println!("{}\n{}", label, drawn);
+    } else {
+        println!("{} {}", label, drawn);
+    }
+}
+
+fn total(hand: &Hand) -> String {
+    let (total, soft) = hand.value();
+    format!("({}{})", if soft { "soft " } else { "" }, total)
+}
+
+fn prompt(lines: &mut impl Iterator<Item = io::Result<String>>, text: &str) -> Option<String> {
+    print!("{}", text);
+    io::stdout().flush().ok()?;
+    lines.next()?.ok().map(|line| line.trim().to_lowercase())
+}
+
+fn parse_action(input: &str) -> Option<PlayerAction> {
+    match input {
+        "h" | "hit" => Some(PlayerAction::Hit),
+        "s" | "stand" => Some(PlayerAction::Stand),
+        "d" | "double" => Some(PlayerAction::Double),
+        "p" | "split" => Some(PlayerAction::Split),
+        "r" | "surrender" => Some(PlayerAction::Surrender),
+        _ => None,
+    }
+}
+
+fn show_result(renderer: &dyn Renderer, result: &RoundResult) {
+    show(renderer, "Dealer:", renderer.hand(&result.dealer));
+    for (hand, outcome) in &result.hands {
+        let outcome = match outcome {
+            Outcome::Blackjack => "blackjack!",
+            Outcome::Win => "win",
+            Outcome::Push => "push",
+            Outcome::Lose => "lose",
+            Outcome::Bust => "bust",
+            Outcome::Surrendered => "surrendered",
+        };
+        let label = format!("  {} bet {}, {}:", total(hand), hand.bet, outcome);
+        show(renderer, &label, renderer.hand(&hand.cards));
+    }
+    if result.insurance != 0 {
+        println!("  insurance: {:+}", result.insurance);
+    }
+    println!("Round: {:+} chips", result.net);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let rules = Rules {
+        dealer_hits_soft_17: args.iter().any(|arg| arg == "--h17"),
+        ..Rules::default()
+    };
+    let seed: Option<u64> = cli::optional_flag(&args, "--seed").unwrap_or_else(|err| cli::usage_error(err));
+    let style: Style = flag(&args, "--cards", Style::Codes).unwrap_or_else(|err| cli::usage_error(err));
+    let renderer = style.renderer();
+    let renderer = renderer.as_ref();
+
+    let mut deck = match seed {
+        Some(seed) => Deck::with_seed(seed),
+        None => Deck::new(),
+    };
+    deck.shuffle();
+    let mut game = Game::new(deck, rules, STARTING_CHIPS);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!(
+        "Blackjack, dealer {} soft 17. Blackjack pays 3:2. Empty bet or 'q' quits.",
+        if game.rules().dealer_hits_soft_17 { "hits" } else { "stands on" }
+    );
+
+    while game.bankroll() > 0 {
+        let input = match prompt(&mut lines, &format!("\nChips: {}. Bet: ", game.bankroll())) {
+            Some(input) if !input.is_empty() && input != "q" => input,
+            _ => break,
+        };
+        let bet: i64 = match input.parse() {
+            Ok(bet) => bet,
+            Err(..) => {
+                println!("'{}' isn't a number", input);
+                continue;
+            }
+        };
+
+        let mut result = match game.start_round(bet) {
+            Ok(result) => result,
+            Err(reason_this_failed) => {
+                println!("{}", reason_this_failed);
+                continue;
+            }
+        };
+        if let Some(upcard) = game.dealer_upcard() {
+            // No upcard if the deal already settled the round
+            show(renderer, "Dealer shows", renderer.hand_with_backs(&[upcard], 1));
+        }
+
+        while result.is_none() && game.insurance_offered() {
+            let hand = &game.hands()[0];
+            show(renderer, &format!("Your hand {}:", total(hand)), renderer.hand(&hand.cards));
+            let take = match prompt(&mut lines, "Insurance? (y/n) ") {
+                Some(answer) => answer.starts_with('y'),
+                None => return,
+            };
+            match game.insurance(take) {
+                Ok(done) => result = done,
+                Err(reason_this_failed) => println!("{}", reason_this_failed),
+            }
+        }
+
+        while result.is_none() {
+            let (index, hand) = game.current_hand().unwrap();
+            let actions: Vec<String> = game
+                .available_actions()
+                .iter()
+                .map(|action| action.to_string())
+                .collect();
+            let label = format!("Hand {} of {} {}:", index + 1, game.hands().len(), total(hand));
+            show(renderer, &label, renderer.hand(&hand.cards));
+            let input = match prompt(&mut lines, &format!("{} (h/s/d/p/r)? ", actions.join(", "))) {
+                Some(input) => input,
+                None => return,
+            };
+            match parse_action(&input) {
+                Some(action) => match game.act(action) {
+                    Ok(done) => result = done,
+                    Err(reason_this_failed) => println!("{}", reason_this_failed),
+                },
+                None => println!("'{}' isn't an action", input),
+            }
+        }
+        show_result(renderer, &result.unwrap());
+    }
+
+    println!("Leaving the table with {} chips", game.bankroll());
+}
@@ -0,0 +1,71 @@
+use basics::bridge::{self, BridgeDeal, Seat};
+use basics::cli::{self, flag};
+use basics::deck::Deck;
+use std::env;
+use std::fs;
+
+/*
+Bridge boards for a club session
+- cargo run -p basics --bin bridge_deals -- [--boards N] [--first N] [--seed N] [--lin]
+- Writes PBN to stdout, or one LIN line per board with --lin. --seed makes the same set again
+- cargo run -p basics --bin bridge_deals -- --read FILE prints each board in a PBN or LIN file
+ */
+fn read(path: &str) -> Result<Vec<BridgeDeal>, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    if text.contains("md|") {
+        let deals: Result<Vec<BridgeDeal>, _> = text
+            .lines()
+            .filter(|line| line.contains("md|"))
+            .map(BridgeDeal::from_lin)
+            .collect();
+        Ok(deals?)
+    } else {
+        Ok(bridge::read_pbn(&text)?)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if let Some(path) = args.iter().position(|arg| arg == "--read").and_then(|index| args.get(index + 1)) {
+        match read(path) {
+            Ok(deals) => {
+                for deal in deals {
+                    println!("{}", deal);
+                    let points: Vec<String> = Seat::ALL
+                        .iter()
+                        .map(|seat| format!("{} {}", seat.letter(), deal.high_card_points(*seat)))
+                        .collect();
+                    println!("HCP: {}\n", points.join(", "));
+                }
+            }
+            Err(reason_this_failed) => eprintln!("{}: {}", path, reason_this_failed),
+        }
+        return;
+    }
+
+    let boards = flag(&args, "--boards", 16u32).unwrap_or_else(|err| cli::usage_error(err));
+    let first = flag(&args, "--first", 1u32).unwrap_or_else(|err| cli::usage_error(err));
+    let seed: Option<u64> = cli::optional_flag(&args, "--seed").unwrap_or_else(|err| cli::usage_error(err));
+    let mut deck = match seed {
+        Some(seed) => Deck::with_seed(seed),
+        None => Deck::new(),
+    };
+
+    let mut deals = vec![];
+    for board in first..first + boards {
+        deck.reshuffle_discards();
+        deck.shuffle();
+        let deal = BridgeDeal::deal(&mut deck, board).expect("a full deck deals four hands");
+        deck.discard(Seat::ALL.iter().flat_map(|seat| deal.hand(*seat).to_vec()).collect());
+        deals.push(deal);
+    }
+
+    if args.iter().any(|arg| arg == "--lin") {
+        for deal in &deals {
+            println!("{}", deal.to_lin());
+        }
+    } else {
+        print!("{}", bridge::write_pbn(&deals));
+    }
+}
@@ -0,0 +1,26 @@
+use basics::server;
+use basics::cli::{self, flag};
+use std::env;
+use std::net::TcpListener;
+
+/*
+Hosts five card draw tables over TCP, see src/server.rs for the protocol
+- cargo run -p basics --bin card_server -- [--port N] [--bind ADDRESS]
+- Only listens on localhost unless given --bind, e.g. --bind 0.0.0.0 to play across machines
+- Then from another terminal: nc localhost 7878, JOIN main yourname
+ */
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let port = flag(&args, "--port", server::DEFAULT_PORT).unwrap_or_else(|err| cli::usage_error(err));
+    let bind = flag(&args, "--bind", String::from("127.0.0.1")).unwrap_or_else(|err| cli::usage_error(err));
+
+    let listener = match TcpListener::bind((bind.as_str(), port)) {
+        Ok(listener) => listener,
+        Err(reason_this_failed) => {
+            eprintln!("Can't listen on {}:{}: {}", bind, port, reason_this_failed);
+            std::process::exit(1);
+        }
+    };
+    println!("Listening on {}:{}", bind, port);
+    server::serve(listener);
+}
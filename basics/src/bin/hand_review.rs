@@ -0,0 +1,73 @@
+use basics::cli;
+use basics::hand_history::{self, Replayer};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+/*
+Steps through PokerStars hand histories, one action at a time
+- cargo run -p basics --bin hand_review -- FILE [--hand N]
+- Enter steps forward, b steps back, n skips to the next hand, q quits
+- With input piped in (or at the end of it) every hand plays straight through
+ */
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("usage: hand_review FILE [--hand N]");
+        return;
+    };
+    let only: Option<u64> = cli::optional_flag(&args, "--hand").unwrap_or_else(|err| cli::usage_error(err));
+
+    let hands = match fs::read_to_string(path).map_err(|err| err.to_string()).and_then(|text| {
+        hand_history::parse_all(&text).map_err(|err| err.to_string())
+    }) {
+        Ok(hands) => hands,
+        Err(reason_this_failed) => {
+            eprintln!("{}: {}", path, reason_this_failed);
+            return;
+        }
+    };
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut interactive = true;
+
+    for history in hands.iter().filter(|history| only.is_none_or(|id| history.id == id)) {
+        println!("\nHand #{}: {}, {} events", history.id, history.game, history.events.len());
+        let mut replayer = Replayer::new(history);
+        print!("{}", replayer.state());
+        loop {
+            let command = if interactive {
+                print!("[{}/{}] ", replayer.position(), replayer.len());
+                io::stdout().flush().ok();
+                match lines.next() {
+                    Some(Ok(line)) => line.trim().to_lowercase(),
+                    _ => {
+                        interactive = false;
+                        String::new()
+                    }
+                }
+            } else {
+                String::new()
+            };
+
+            match command.as_str() {
+                "q" => return,
+                "n" => break,
+                "b" => {
+                    if replayer.back() {
+                        println!("back to {}", replayer.position());
+                        print!("{}", replayer.state());
+                    }
+                }
+                _ => match replayer.forward() {
+                    Some(event) => {
+                        println!("{}", event);
+                        print!("{}", replayer.state());
+                    }
+                    None => break,
+                },
+            }
+        }
+    }
+}
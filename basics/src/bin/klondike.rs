@@ -0,0 +1,87 @@
+use basics::klondike::{self, Klondike, Rules, Solution};
+use basics::cli::{self, flag};
+use std::env;
+use std::time::Instant;
+
+/*
+Solves seeded Klondike deals
+- cargo run --release -p basics --bin klondike -- [--seed N] [--games N] [--draw 1|3] [--redeals N] [--limit N]
+- One game prints the deal and the winning moves, more than one prints a line each and the totals
+- Deals that hit the node limit count as unknown. The solver skips some moves, so a deal it finds no
+  solution for is very likely unwinnable but not proven
+ */
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let first_seed = flag(&args, "--seed", 1u64).unwrap_or_else(|err| cli::usage_error(err));
+    let games = flag(&args, "--games", 1u64).unwrap_or_else(|err| cli::usage_error(err));
+    let limit = flag(&args, "--limit", 200_000u64).unwrap_or_else(|err| cli::usage_error(err));
+    let rules = Rules {
+        draw: flag(&args, "--draw", 1usize).unwrap_or_else(|err| cli::usage_error(err)),
+        redeals: cli::optional_flag(&args, "--redeals").unwrap_or_else(|err| cli::usage_error(err)),
+    };
+
+    let (mut won, mut lost, mut unknown) = (0, 0, 0);
+    for seed in first_seed..first_seed + games {
+        let mut game = match Klondike::seeded(seed, rules) {
+            Ok(game) => game,
+            Err(reason_this_failed) => {
+                eprintln!("{}", reason_this_failed);
+                std::process::exit(2);
+            }
+        };
+        if games == 1 {
+            print!("{}", game.board());
+        }
+
+        let started = Instant::now();
+        let report = klondike::solve(game.board(), limit);
+        let verdict = match &report.solution {
+            Solution::Solved(moves) => {
+                won += 1;
+                // Play it through the game to check every move and get the score
+                for next in moves {
+                    if games == 1 {
+                        println!("  {}", next);
+                    }
+                    game.play(*next).expect("solver moves are legal");
+                }
+                format!("won in {} moves, score {}", moves.len(), game.score())
+            }
+            Solution::NoSolutionFound => {
+                lost += 1;
+                String::from("no solution found")
+            }
+            Solution::GaveUp => {
+                unknown += 1;
+                String::from("gave up")
+            }
+        };
+        println!(
+            "Seed {}: {} ({} positions, {:.1?})",
+            seed,
+            verdict,
+            report.nodes,
+            started.elapsed()
+        );
+
+        if games == 1 && game.is_won() {
+            game.undo().unwrap();
+            game.undo().unwrap();
+            println!("Two undos: won {}, score {}", game.is_won(), game.score());
+            game.redo().unwrap();
+            game.redo().unwrap();
+            println!("Two redos: won {}, score {}", game.is_won(), game.score());
+        }
+    }
+
+    if games > 1 {
+        println!(
+            "Won {}, no solution found {}, unknown {} of {} ({:.1}% winnable at least)",
+            won,
+            lost,
+            unknown,
+            games,
+            100.0 * won as f64 / games as f64
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use basics::shuffle_quality;
+use basics::shuffles::Technique;
+use basics::cli::{self, flag};
+use std::env;
+use std::time::Instant;
+
+/*
+Checks Deck::shuffle for bias and compares it with the human shuffles
+- cargo run --release -p basics --bin shuffle_quality -- [--trials N] [--seed N] [--cards N]
+- Riffles should only start to look random around 7 to 10, overhands around 100, a cut never
+ */
+const ALPHA: f64 = 0.001;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let trials = flag(&args, "--trials", 100_000u64).unwrap_or_else(|err| cli::usage_error(err));
+    let seed = flag(&args, "--seed", 1u64).unwrap_or_else(|err| cli::usage_error(err));
+    let cards = flag(&args, "--cards", 52usize).unwrap_or_else(|err| cli::usage_error(err));
+    if let Err(reason_this_failed) = shuffle_quality::check(cards, trials) {
+        eprintln!("{}", reason_this_failed);
+        std::process::exit(2);
+    }
+
+    println!(
+        "{} trials, {} cards, seed {}. Random decks average {:.3} adjacent pairs and {:.1} rising sequences",
+        trials,
+        cards,
+        seed,
+        (cards - 1) as f64 / cards as f64,
+        (cards + 1) as f64 / 2.0
+    );
+
+    let runs = [
+        (Technique::FisherYates, 1),
+        (Technique::Riffle, 1),
+        (Technique::Riffle, 3),
+        (Technique::Riffle, 5),
+        (Technique::Riffle, 7),
+        (Technique::Riffle, 10),
+        (Technique::Overhand, 1),
+        (Technique::Overhand, 10),
+        (Technique::Overhand, 100),
+        (Technique::Cut, 1),
+    ];
+    let mut deck_shuffle_ok = true;
+    for (technique, passes) in runs {
+        let started = Instant::now();
+        let report = shuffle_quality::measure(technique, passes, trials, cards, seed).expect("checked above");
+        let verdict = if report.looks_random(ALPHA) { "random" } else { "BIASED" };
+        println!("{}  {} [{:.1?}]", report, verdict, started.elapsed());
+        if technique == Technique::FisherYates && !report.looks_random(ALPHA) {
+            deck_shuffle_ok = false;
+        }
+    }
+
+    if !deck_shuffle_ok {
+        println!("Deck::shuffle failed at significance {}", ALPHA);
+        std::process::exit(1);
+    }
+}
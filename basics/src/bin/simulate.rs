@@ -0,0 +1,33 @@
+use basics::blackjack::Rules;
+use basics::simulation::{self, BasicStrategy, MimicDealer};
+use basics::cli::{self, flag};
+use std::env;
+use std::thread;
+use std::time::Instant;
+
+/*
+Estimates Blackjack odds for a couple of strategies
+- cargo run --release -p basics --bin simulate -- [--hands N] [--threads N] [--seed N] [--h17]
+- Same seed and thread count gives the same numbers
+ */
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let hands = flag(&args, "--hands", 1_000_000u64).unwrap_or_else(|err| cli::usage_error(err));
+    let threads = flag(&args, "--threads", cores).unwrap_or_else(|err| cli::usage_error(err));
+    let seed = flag(&args, "--seed", 1u64).unwrap_or_else(|err| cli::usage_error(err));
+    let rules = Rules {
+        dealer_hits_soft_17: args.iter().any(|arg| arg == "--h17"),
+        ..Rules::default()
+    };
+
+    println!("{} hands on {} threads, seed {}", hands, threads, seed);
+
+    let started = Instant::now();
+    let report = simulation::simulate_blackjack(&BasicStrategy, &rules, hands, threads, seed);
+    println!("Basic strategy:  {} [{:.1?}]", report, started.elapsed());
+
+    let started = Instant::now();
+    let report = simulation::simulate_blackjack(&MimicDealer, &rules, hands, threads, seed);
+    println!("Mimic the dealer: {} [{:.1?}]", report, started.elapsed());
+}
@@ -0,0 +1,157 @@
+use basics::deck::Deck;
+use basics::cli::{self, flag};
+use std::env;
+use std::fmt;
+
+/*
+Deck<C> with things that aren't playing cards
+- cargo run -p basics --bin tiles -- [--players N] [--seed N]
+- A bag of word game letter tiles: racks of 7, then an exchange (tiles back in the bag, new ones out)
+- A trading card deck: an opening hand, a mulligan, and the next turn's draw
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tile {
+    letter: char, // ' ' for a blank
+    points: u8,
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.letter {
+            ' ' => write!(f, "_"),
+            letter => write!(f, "{}{}", letter, self.points),
+        }
+    }
+}
+
+// (letter, points, copies), the English word game set of 100 tiles. ' ' is the blank
+const TILE_SET: [(char, u8, usize); 27] = [
+    ('A', 1, 9),
+    ('B', 3, 2),
+    ('C', 3, 2),
+    ('D', 2, 4),
+    ('E', 1, 12),
+    ('F', 4, 2),
+    ('G', 2, 3),
+    ('H', 4, 2),
+    ('I', 1, 9),
+    ('J', 8, 1),
+    ('K', 5, 1),
+    ('L', 1, 4),
+    ('M', 3, 2),
+    ('N', 1, 6),
+    ('O', 1, 8),
+    ('P', 3, 2),
+    ('Q', 10, 1),
+    ('R', 1, 6),
+    ('S', 1, 4),
+    ('T', 1, 6),
+    ('U', 1, 4),
+    ('V', 4, 2),
+    ('W', 4, 2),
+    ('X', 8, 1),
+    ('Y', 4, 2),
+    ('Z', 10, 1),
+    (' ', 0, 2),
+];
+
+fn tile_bag() -> Vec<Tile> {
+    TILE_SET
+        .iter()
+        .flat_map(|(letter, points, copies)| {
+            (0..*copies).map(|_| Tile {
+                letter: *letter,
+                points: *points,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Creature {
+    name: &'static str,
+    cost: u8,
+    power: u8,
+}
+
+impl fmt::Display for Creature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}/{})", self.name, self.cost, self.power)
+    }
+}
+
+const CREATURES: [(&str, u8, u8); 10] = [
+    ("Goblin", 1, 1),
+    ("Wolf", 2, 2),
+    ("Archer", 2, 1),
+    ("Knight", 3, 3),
+    ("Troll", 4, 5),
+    ("Griffin", 4, 3),
+    ("Giant", 5, 6),
+    ("Wizard", 3, 2),
+    ("Hydra", 6, 7),
+    ("Dragon", 7, 8),
+];
+
+fn names<T: fmt::Display>(items: &[T]) -> String {
+    items.iter().map(|item| item.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let players = flag(&args, "--players", 2usize).unwrap_or_else(|err| cli::usage_error(err));
+    let seed = flag(&args, "--seed", 1u64).unwrap_or_else(|err| cli::usage_error(err));
+
+    let mut bag = Deck::from_cards_seeded(tile_bag(), seed);
+    bag.shuffle();
+    println!("{} tiles in the bag", bag.remaining());
+    let mut racks = vec![];
+    for player in 1..=players {
+        match bag.deal(7) {
+            Ok(rack) => {
+                println!("Player {}: {}", player, names(&rack));
+                racks.push(rack);
+            }
+            Err(reason_this_failed) => {
+                println!("Player {}: {}", player, reason_this_failed);
+                return;
+            }
+        }
+    }
+    // Player 1 swaps their three lowest tiles, which go back in the bag only after the new ones are drawn
+    if let Some(rack) = racks.first_mut() {
+        rack.sort_by_key(|tile| tile.points);
+        let returned: Vec<Tile> = rack.drain(..3).collect();
+        rack.extend(bag.deal_up_to(3));
+        println!("Player 1 exchanges {}, new rack {}", names(&returned), names(rack));
+        bag.discard(returned);
+        bag.reshuffle_discards();
+        println!("{} tiles left in the bag", bag.remaining());
+    }
+
+    // Four of each creature, collected straight into a deck
+    let mut library: Deck<Creature> = CREATURES
+        .iter()
+        .flat_map(|(name, cost, power)| (0..4).map(move |_| Creature { name, cost: *cost, power: *power }))
+        .collect();
+    library.shuffle_seeded(seed);
+    let hand = library.deal(7).expect("40 cards in the deck");
+    println!("\nOpening hand: {}", names(&hand));
+    // Mulligan anything without a creature costing 2 or less: shuffle the hand back, draw one fewer
+    let hand = if hand.iter().any(|creature| creature.cost <= 2) {
+        hand
+    } else {
+        library.discard(hand);
+        library.reshuffle_discards();
+        library.shuffle();
+        let hand = library.deal(6).expect("40 cards in the deck");
+        println!("Mulligan to six: {}", names(&hand));
+        hand
+    };
+    let total_cost: u32 = hand.iter().map(|creature| creature.cost as u32).sum();
+    println!("Average cost {:.1}", total_cost as f64 / hand.len() as f64);
+    match library.draw_one() {
+        Some(creature) => println!("Turn 1 draw: {}, {} cards left", creature, library.remaining()),
+        None => println!("The deck is empty"),
+    }
+}
@@ -0,0 +1,90 @@
+use basics::draw_poker::{self, DrawBot, HeadsUp, KeepGroups, StandPat, Tight};
+use basics::hand_history;
+use basics::poker::Category;
+use basics::tournament::{Format, MatchPlay, Outcome, Tournament};
+use basics::cli::{self, flag};
+use std::env;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/*
+Draw poker bots in a tournament
+- cargo run -p basics --bin tournament -- [--format round-robin|swiss|swiss:N|elimination] [--seed N] [--hands N]
+- Each match is heads up: 100 chips each, ante 5, until --hands hands (default 50) or someone is broke
+- The report lists every match's replay seed. To get one match's PokerStars hand histories:
+  cargo run -p basics --bin tournament -- --replay SEED --first NAME --second NAME [--hands N] [--history FILE]
+  then step through them with the hand_review bin
+ */
+const CHIPS: i64 = 100;
+const ANTE: i64 = 5;
+
+type Bots = Vec<(&'static str, Box<dyn DrawBot>)>;
+
+fn entrants() -> Bots {
+    vec![
+        ("KeepGroups", Box::new(KeepGroups)),
+        ("TightPair", Box::new(Tight { min: Category::OnePair })),
+        ("TightTwoPair", Box::new(Tight { min: Category::TwoPair })),
+        ("StandPat", Box::new(StandPat)),
+        ("TightTrips", Box::new(Tight { min: Category::ThreeOfAKind })),
+    ]
+}
+
+fn play(bots: &Bots, first: usize, second: usize, hands: usize, seed: u64, time: u64) -> HeadsUp {
+    let players = [
+        (bots[first].0, bots[first].1.as_ref()),
+        (bots[second].0, bots[second].1.as_ref()),
+    ];
+    draw_poker::heads_up(players, CHIPS, ANTE, hands, seed, time)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let hands = flag(&args, "--hands", 50usize).unwrap_or_else(|err| cli::usage_error(err));
+    let seed = flag(&args, "--seed", 1u64).unwrap_or_else(|err| cli::usage_error(err));
+    let bots = entrants();
+    let find = |name: &str| bots.iter().position(|(bot, _)| bot.eq_ignore_ascii_case(name));
+
+    let replay: Option<u64> = cli::optional_flag(&args, "--replay").unwrap_or_else(|err| cli::usage_error(err));
+    if let Some(seed) = replay {
+        let first = find(&flag(&args, "--first", String::new()).unwrap_or_else(|err| cli::usage_error(err)));
+        let second = find(&flag(&args, "--second", String::new()).unwrap_or_else(|err| cli::usage_error(err)));
+        let (Some(first), Some(second)) = (first, second) else {
+            let names: Vec<&str> = bots.iter().map(|(name, _)| *name).collect();
+            eprintln!("--replay needs --first and --second, one of {}", names.join(", "));
+            return;
+        };
+        // The histories are dated now, the cards and chips only depend on the seed
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let played = play(&bots, first, second, hands, seed, now);
+        let text = hand_history::write_all(&played.histories);
+        match args.iter().position(|arg| arg == "--history").and_then(|index| args.get(index + 1)) {
+            Some(path) => match fs::write(path, text) {
+                Ok(()) => println!("{} hands written to {}", played.hands_played, path),
+                Err(reason_this_failed) => eprintln!("{}: {}", path, reason_this_failed),
+            },
+            None => print!("{}", text),
+        }
+        println!("Chips: {} to {}", played.chips[0], played.chips[1]);
+        return;
+    }
+
+    let format: Format = flag(&args, "--format", Format::RoundRobin).unwrap_or_else(|err| cli::usage_error(err));
+    let names = bots.iter().map(|(name, _)| name.to_string()).collect();
+    let tournament = Tournament::new(names, format, seed).expect("there are several bots");
+    let report = tournament.run(|first, second, seed| {
+        let played = play(&bots, first, second, hands, seed, 0); // Histories aren't kept, the time doesn't matter
+        let [first_chips, second_chips] = played.chips;
+        MatchPlay {
+            outcome: match first_chips.cmp(&second_chips) {
+                std::cmp::Ordering::Greater => Outcome::FirstWins,
+                std::cmp::Ordering::Less => Outcome::SecondWins,
+                std::cmp::Ordering::Equal => Outcome::Draw,
+            },
+            summary: format!("{} to {} in {} hands", first_chips, second_chips, played.hands_played),
+        }
+    });
+    println!("{}", report);
+}
@@ -0,0 +1,526 @@
+use super::card::{Card, Rank};
+use super::deck::Deck;
+use std::fmt;
+
+/**
+Blackjack engine
+
+- Game owns the deck and the player's bankroll, one player against the dealer
+- A round: start_round(bet) -> insurance decision if the dealer shows an Ace -> act() on each hand -> settled
+- The dealer checks for blackjack (peeks) before the player acts, so surrender is late surrender
+- Chips are whole numbers, a 3:2 blackjack on an odd bet rounds down
+- Cards from a finished round go to the deck's discard pile, reshuffled back in when the deck runs low
+- Running out of cards never costs a bet. On the deal or a double or split nothing is taken, and if the
+  dealer can't finish drawing the round is called off with every bet returned
+*/
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub dealer_hits_soft_17: bool,
+    pub double_after_split: bool,
+    pub surrender_allowed: bool,
+    pub max_hands: usize, // Hands a player can split into
+    pub reshuffle_below: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            dealer_hits_soft_17: false,
+            double_after_split: true,
+            surrender_allowed: true,
+            max_hands: 4,
+            reshuffle_below: 15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayerAction {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl fmt::Display for PlayerAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PlayerAction::Hit => "hit",
+            PlayerAction::Stand => "stand",
+            PlayerAction::Double => "double",
+            PlayerAction::Split => "split",
+            PlayerAction::Surrender => "surrender",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlackjackError {
+    RoundInProgress,
+    NoRoundInProgress,
+    InvalidBet(i64),
+    InsufficientFunds { needed: i64, bankroll: i64 },
+    ActionNotAllowed(PlayerAction),
+    InsuranceNotOffered,
+    InsuranceUndecided,
+    OutOfCards,
+}
+
+impl fmt::Display for BlackjackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlackjackError::RoundInProgress => write!(f, "finish the current round first"),
+            BlackjackError::NoRoundInProgress => write!(f, "no round in progress, place a bet"),
+            BlackjackError::InvalidBet(bet) => write!(f, "bet must be positive, got {}", bet),
+            BlackjackError::InsufficientFunds { needed, bankroll } => {
+                write!(f, "need {} chips but only have {}", needed, bankroll)
+            }
+            BlackjackError::ActionNotAllowed(action) => write!(f, "can't {} right now", action),
+            BlackjackError::InsuranceNotOffered => write!(f, "insurance is only offered against an Ace"),
+            BlackjackError::InsuranceUndecided => write!(f, "decide on insurance first"),
+            BlackjackError::OutOfCards => write!(f, "the deck ran out of cards"),
+        }
+    }
+}
+
+impl std::error::Error for BlackjackError {}
+
+/// Tens and face cards are 10, Aces count 1 here and hand_value decides when they're 11
+pub fn card_value(card: &Card) -> u32 {
+    match card.rank {
+        Rank::Ace => 1,
+        Rank::Jack | Rank::Queen | Rank::King => 10,
+        rank => rank.value() as u32,
+    }
+}
+
+/// Best total and whether it's soft (an Ace is counting as 11)
+pub fn hand_value(cards: &[Card]) -> (u32, bool) {
+    let hard: u32 = cards.iter().map(card_value).sum();
+    let has_ace = cards.iter().any(|card| card.rank == Rank::Ace);
+    if has_ace && hard + 10 <= 21 {
+        (hard + 10, true)
+    } else {
+        (hard, false)
+    }
+}
+
+pub fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && hand_value(cards).0 == 21
+}
+
+#[derive(Debug, Clone)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub bet: i64,
+    pub from_split: bool,
+    pub doubled: bool,
+    pub surrendered: bool,
+    pub finished: bool,
+}
+
+impl Hand {
+    fn new(cards: Vec<Card>, bet: i64, from_split: bool) -> Self {
+        Hand {
+            cards,
+            bet,
+            from_split,
+            doubled: false,
+            surrendered: false,
+            finished: false,
+        }
+    }
+
+    pub fn value(&self) -> (u32, bool) {
+        hand_value(&self.cards)
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.value().0 > 21
+    }
+
+    /// Only an unsplit two card 21 counts as blackjack
+    pub fn is_blackjack(&self) -> bool {
+        !self.from_split && is_blackjack(&self.cards)
+    }
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let codes: Vec<String> = self.cards.iter().map(|card| card.code()).collect();
+        let (total, soft) = self.value();
+        write!(f, "{} ({}{})", codes.join(" "), if soft { "soft " } else { "" }, total)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Blackjack,
+    Win,
+    Push,
+    Lose,
+    Bust,
+    Surrendered,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoundResult {
+    pub hands: Vec<(Hand, Outcome)>,
+    pub dealer: Vec<Card>,
+    pub insurance: i64, // Net chips from the insurance bet, 0 if none was taken
+    pub net: i64,       // Net chips for the whole round, insurance included
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Insurance,
+    Playing,
+}
+
+#[derive(Debug)]
+struct Round {
+    hands: Vec<Hand>,
+    active: usize,
+    dealer: Vec<Card>,
+    insurance: i64,
+    phase: Phase,
+}
+
+#[derive(Debug)]
+pub struct Game {
+    deck: Deck,
+    rules: Rules,
+    bankroll: i64,
+    round: Option<Round>,
+}
+
+impl Game {
+    /// The deck is passed in so callers choose how it's shuffled (seeded for replays and simulations)
+    pub fn new(deck: Deck, rules: Rules, bankroll: i64) -> Self {
+        Game {
+            deck,
+            rules,
+            bankroll,
+            round: None,
+        }
+    }
+
+    pub fn bankroll(&self) -> i64 {
+        self.bankroll
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn in_round(&self) -> bool {
+        self.round.is_some()
+    }
+
+    // Checked before any chips change hands so running out can't cost the player a bet.
+    // draw() reshuffles the discards once the deck is empty, so they count too
+    fn ensure_cards(&self, needed: usize) -> Result<(), BlackjackError> {
+        if self.deck.remaining() + self.deck.discards().len() < needed {
+            return Err(BlackjackError::OutOfCards);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Result<Card, BlackjackError> {
+        if self.deck.remaining() == 0 {
+            self.deck.reshuffle_discards();
+        }
+        self.deck.draw_one().ok_or(BlackjackError::OutOfCards)
+    }
+
+    /// Takes the bet and deals. Returns the result straight away if the round ends on the deal
+    pub fn start_round(&mut self, bet: i64) -> Result<Option<RoundResult>, BlackjackError> {
+        if self.round.is_some() {
+            return Err(BlackjackError::RoundInProgress);
+        }
+        if bet <= 0 {
+            return Err(BlackjackError::InvalidBet(bet));
+        }
+        self.ensure_cards(4)?;
+        self.charge(bet)?;
+
+        if self.deck.remaining() < self.rules.reshuffle_below {
+            self.deck.reshuffle_discards();
+        }
+        let player = vec![self.draw()?, self.draw()?];
+        let dealer = vec![self.draw()?, self.draw()?];
+
+        let phase = if dealer[0].rank == Rank::Ace {
+            Phase::Insurance
+        } else {
+            Phase::Playing
+        };
+        self.round = Some(Round {
+            hands: vec![Hand::new(player, bet, false)],
+            active: 0,
+            dealer,
+            insurance: 0,
+            phase,
+        });
+
+        if phase == Phase::Playing {
+            return self.after_peek();
+        }
+        Ok(None)
+    }
+
+    /// Insurance costs half the bet and pays 2:1 if the dealer has blackjack
+    pub fn insurance(&mut self, take: bool) -> Result<Option<RoundResult>, BlackjackError> {
+        let round = self.round.as_ref().ok_or(BlackjackError::NoRoundInProgress)?;
+        if round.phase != Phase::Insurance {
+            return Err(BlackjackError::InsuranceNotOffered);
+        }
+
+        if take {
+            let cost = round.hands[0].bet / 2;
+            self.charge(cost)?;
+            self.round.as_mut().unwrap().insurance = cost;
+        }
+        self.round.as_mut().unwrap().phase = Phase::Playing;
+        self.after_peek()
+    }
+
+    pub fn insurance_offered(&self) -> bool {
+        matches!(&self.round, Some(round) if round.phase == Phase::Insurance)
+    }
+
+    // The dealer peeks for blackjack, a blackjack on either side ends the round
+    fn after_peek(&mut self) -> Result<Option<RoundResult>, BlackjackError> {
+        let round = self.round.as_ref().unwrap();
+        if is_blackjack(&round.dealer) || round.hands[0].is_blackjack() {
+            return Ok(Some(self.finish_round()?));
+        }
+        Ok(None)
+    }
+
+    pub fn dealer_upcard(&self) -> Option<Card> {
+        self.round.as_ref().map(|round| round.dealer[0])
+    }
+
+    pub fn hands(&self) -> &[Hand] {
+        self.round.as_ref().map(|round| round.hands.as_slice()).unwrap_or(&[])
+    }
+
+    /// The hand waiting for a decision and its position among the player's hands
+    pub fn current_hand(&self) -> Option<(usize, &Hand)> {
+        let round = self.round.as_ref()?;
+        round.hands.get(round.active).map(|hand| (round.active, hand))
+    }
+
+    pub fn available_actions(&self) -> Vec<PlayerAction> {
+        let round = match &self.round {
+            Some(round) if round.phase == Phase::Playing => round,
+            _ => return vec![],
+        };
+        let hand = match round.hands.get(round.active) {
+            Some(hand) => hand,
+            None => return vec![],
+        };
+
+        // A split hand can land on 21 without a decision, nothing to do but stand
+        if hand.value().0 >= 21 {
+            return vec![PlayerAction::Stand];
+        }
+
+        let mut actions = vec![PlayerAction::Hit, PlayerAction::Stand];
+        let first_decision = hand.cards.len() == 2;
+        let can_afford = self.bankroll >= hand.bet;
+
+        if first_decision && can_afford && (!hand.from_split || self.rules.double_after_split) {
+            actions.push(PlayerAction::Double);
+        }
+        if first_decision
+            && can_afford
+            && round.hands.len() < self.rules.max_hands
+            && card_value(&hand.cards[0]) == card_value(&hand.cards[1])
+        {
+            actions.push(PlayerAction::Split);
+        }
+        if first_decision && self.rules.surrender_allowed && round.hands.len() == 1 {
+            actions.push(PlayerAction::Surrender);
+        }
+        actions
+    }
+
+    /// Plays one decision on the current hand. Returns the result once the last hand is done
+    pub fn act(&mut self, action: PlayerAction) -> Result<Option<RoundResult>, BlackjackError> {
+        match &self.round {
+            None => return Err(BlackjackError::NoRoundInProgress),
+            Some(round) if round.phase == Phase::Insurance => {
+                return Err(BlackjackError::InsuranceUndecided)
+            }
+            _ => {}
+        }
+        if !self.available_actions().contains(&action) {
+            return Err(BlackjackError::ActionNotAllowed(action));
+        }
+
+        let active = self.round.as_ref().unwrap().active;
+        match action {
+            PlayerAction::Hit => {
+                let card = self.draw()?;
+                let hand = &mut self.round.as_mut().unwrap().hands[active];
+                hand.cards.push(card);
+                hand.finished = hand.value().0 >= 21;
+            }
+            PlayerAction::Stand => {
+                self.round.as_mut().unwrap().hands[active].finished = true;
+            }
+            PlayerAction::Double => {
+                let bet = self.round.as_ref().unwrap().hands[active].bet;
+                self.ensure_cards(1)?;
+                self.charge(bet)?;
+                let card = self.draw()?;
+                let hand = &mut self.round.as_mut().unwrap().hands[active];
+                hand.bet += bet;
+                hand.doubled = true;
+                hand.cards.push(card);
+                hand.finished = true;
+            }
+            PlayerAction::Split => {
+                let bet = self.round.as_ref().unwrap().hands[active].bet;
+                self.ensure_cards(2)?;
+                self.charge(bet)?;
+                let (first, second) = (self.draw()?, self.draw()?);
+                let round = self.round.as_mut().unwrap();
+                let moved = round.hands[active].cards.pop().unwrap();
+                let hand = &mut round.hands[active];
+                hand.from_split = true;
+                hand.cards.push(first);
+                let mut split = Hand::new(vec![moved, second], bet, true);
+
+                // Split Aces get one card each and no more decisions
+                if moved.rank == Rank::Ace {
+                    round.hands[active].finished = true;
+                    split.finished = true;
+                }
+                round.hands.insert(active + 1, split);
+            }
+            PlayerAction::Surrender => {
+                let hand = &mut self.round.as_mut().unwrap().hands[active];
+                hand.surrendered = true;
+                hand.finished = true;
+            }
+        }
+
+        let round = self.round.as_mut().unwrap();
+        while round.active < round.hands.len() && round.hands[round.active].finished {
+            round.active += 1;
+        }
+        if round.active == round.hands.len() {
+            return Ok(Some(self.finish_round()?));
+        }
+        Ok(None)
+    }
+
+    fn charge(&mut self, amount: i64) -> Result<(), BlackjackError> {
+        if amount > self.bankroll {
+            return Err(BlackjackError::InsufficientFunds {
+                needed: amount,
+                bankroll: self.bankroll,
+            });
+        }
+        self.bankroll -= amount;
+        Ok(())
+    }
+
+    // Gives back every bet, insurance included, and puts all the round's cards in the discards. A game
+    // out of cards can then start a new round once cards come back, instead of being stuck mid-round
+    fn call_off(&mut self, dealer: Vec<Card>) {
+        let round = self.round.take().unwrap();
+        self.bankroll += round.insurance + round.hands.iter().map(|hand| hand.bet).sum::<i64>();
+        let mut used = dealer;
+        for hand in round.hands {
+            used.extend(hand.cards);
+        }
+        self.deck.discard(used);
+    }
+
+    fn dealer_should_hit(&self, dealer: &[Card]) -> bool {
+        let (total, soft) = hand_value(dealer);
+        total < 17 || (total == 17 && soft && self.rules.dealer_hits_soft_17)
+    }
+
+    // If the cards run out while the dealer draws the round is called off, see call_off()
+    fn finish_round(&mut self) -> Result<RoundResult, BlackjackError> {
+        let round = self.round.as_ref().unwrap();
+        let mut dealer = round.dealer.clone();
+        let dealer_blackjack = is_blackjack(&dealer);
+
+        // Dealer only draws if some hand is still waiting to be compared
+        let live = round
+            .hands
+            .iter()
+            .any(|hand| !hand.is_bust() && !hand.surrendered && !hand.is_blackjack());
+        if live && !dealer_blackjack {
+            while self.dealer_should_hit(&dealer) {
+                match self.draw() {
+                    Ok(card) => dealer.push(card),
+                    Err(reason_this_failed) => {
+                        self.call_off(dealer);
+                        return Err(reason_this_failed);
+                    }
+                }
+            }
+        }
+        let mut round = self.round.take().unwrap();
+        round.dealer = dealer;
+        let dealer_total = hand_value(&round.dealer).0;
+
+        let mut net = 0;
+        let mut hands = vec![];
+        for hand in round.hands {
+            let (outcome, paid) = if hand.surrendered {
+                (Outcome::Surrendered, hand.bet / 2)
+            } else if hand.is_bust() {
+                (Outcome::Bust, 0)
+            } else if hand.is_blackjack() && !dealer_blackjack {
+                (Outcome::Blackjack, hand.bet + hand.bet * 3 / 2)
+            } else if dealer_blackjack {
+                if hand.is_blackjack() {
+                    (Outcome::Push, hand.bet)
+                } else {
+                    (Outcome::Lose, 0)
+                }
+            } else if dealer_total > 21 || hand.value().0 > dealer_total {
+                (Outcome::Win, hand.bet * 2)
+            } else if hand.value().0 == dealer_total {
+                (Outcome::Push, hand.bet)
+            } else {
+                (Outcome::Lose, 0)
+            };
+            self.bankroll += paid;
+            net += paid - hand.bet;
+            hands.push((hand, outcome));
+        }
+
+        let insurance = if round.insurance == 0 {
+            0
+        } else if dealer_blackjack {
+            self.bankroll += round.insurance * 3;
+            round.insurance * 2
+        } else {
+            -round.insurance
+        };
+
+        let mut used: Vec<Card> = round.dealer.clone();
+        for (hand, _) in &hands {
+            used.extend(hand.cards.iter().copied());
+        }
+        self.deck.discard(used);
+
+        Ok(RoundResult {
+            hands,
+            dealer: round.dealer,
+            insurance,
+            net: net + insurance,
+        })
+    }
+}
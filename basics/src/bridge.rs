@@ -0,0 +1,453 @@
+use super::card::{Card, Rank, Suit};
+use super::deck::{DealError, Deck};
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Bridge deals and the two common hand record formats
+
+- Four hands of 13, dealt one at a time clockwise starting left of the dealer
+- Board numbers fix the dealer and vulnerability, repeating every 16 boards (dealer N, E, S, W, ...)
+- PBN (Portable Bridge Notation): tag lines, [Dealer "N"] [Vulnerable "NS"] [Deal "N:AKQ.JT9.876.5432 ..."].
+  The Deal tag names the first hand's seat, then four hands clockwise, suits S.H.D.C, T for ten
+- LIN (Bridge Base Online): key|value| pairs on one line. md|3S...H...D...C...,...| starts with the
+  dealer (1 South, 2 West, 3 North, 4 East) then the hands South, West, North, East. The last hand
+  can be left out. sv|o| is the vulnerability: o none, n NS, e EW, b both. ah|Board 7| names the board
+- Reading checks for exactly 52 different cards, 13 in each hand
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Seat {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Seat {
+    pub const ALL: [Seat; 4] = [Seat::North, Seat::East, Seat::South, Seat::West];
+
+    pub fn letter(&self) -> char {
+        match self {
+            Seat::North => 'N',
+            Seat::East => 'E',
+            Seat::South => 'S',
+            Seat::West => 'W',
+        }
+    }
+
+    /// Clockwise, the player on this seat's left
+    pub fn next(&self) -> Seat {
+        Seat::ALL[(*self as usize + 1) % 4]
+    }
+
+    // LIN numbers seats from South
+    fn lin_number(&self) -> u8 {
+        match self {
+            Seat::South => 1,
+            Seat::West => 2,
+            Seat::North => 3,
+            Seat::East => 4,
+        }
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Seat::North => "North",
+            Seat::East => "East",
+            Seat::South => "South",
+            Seat::West => "West",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Seat {
+    type Err = BridgeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Seat::ALL
+            .into_iter()
+            .find(|seat| {
+                seat.to_string().eq_ignore_ascii_case(text) || text.eq_ignore_ascii_case(&seat.letter().to_string())
+            })
+            .ok_or_else(|| BridgeError::Parse(format!("'{}' isn't a seat", text)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vulnerability {
+    None,
+    NorthSouth,
+    EastWest,
+    Both,
+}
+
+impl Vulnerability {
+    pub fn pbn(&self) -> &'static str {
+        match self {
+            Vulnerability::None => "None",
+            Vulnerability::NorthSouth => "NS",
+            Vulnerability::EastWest => "EW",
+            Vulnerability::Both => "All",
+        }
+    }
+
+    fn lin(&self) -> char {
+        match self {
+            Vulnerability::None => 'o',
+            Vulnerability::NorthSouth => 'n',
+            Vulnerability::EastWest => 'e',
+            Vulnerability::Both => 'b',
+        }
+    }
+
+    // PBN allows a few spellings
+    fn from_pbn(text: &str) -> Result<Self, BridgeError> {
+        match text.to_ascii_lowercase().as_str() {
+            "none" | "love" | "-" => Ok(Vulnerability::None),
+            "ns" => Ok(Vulnerability::NorthSouth),
+            "ew" => Ok(Vulnerability::EastWest),
+            "all" | "both" => Ok(Vulnerability::Both),
+            _ => Err(BridgeError::Parse(format!("'{}' isn't a vulnerability", text))),
+        }
+    }
+
+    fn from_lin(text: &str) -> Result<Self, BridgeError> {
+        match text.to_ascii_lowercase().as_str() {
+            "o" | "0" | "-" => Ok(Vulnerability::None),
+            "n" => Ok(Vulnerability::NorthSouth),
+            "e" => Ok(Vulnerability::EastWest),
+            "b" => Ok(Vulnerability::Both),
+            _ => Err(BridgeError::Parse(format!("'{}' isn't a LIN vulnerability", text))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BridgeError {
+    Deal(DealError),
+    Parse(String),
+    InvalidDeal(String),
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BridgeError::Deal(reason) => write!(f, "{}", reason),
+            BridgeError::Parse(reason) => write!(f, "{}", reason),
+            BridgeError::InvalidDeal(reason) => write!(f, "not a valid deal: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+impl From<DealError> for BridgeError {
+    fn from(err: DealError) -> Self {
+        BridgeError::Deal(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeDeal {
+    pub board: Option<u32>,
+    pub dealer: Seat,
+    pub vulnerability: Vulnerability,
+    hands: [Vec<Card>; 4], // By Seat, each sorted spades first then high to low
+}
+
+/// Dealer and vulnerability for a board number, the standard 16 board cycle
+pub fn board_conditions(board: u32) -> (Seat, Vulnerability) {
+    use Vulnerability::*;
+    const CYCLE: [Vulnerability; 16] = [
+        None, NorthSouth, EastWest, Both, NorthSouth, EastWest, Both, None, EastWest, Both, None, NorthSouth, Both, None,
+        NorthSouth, EastWest,
+    ];
+    let index = (board.max(1) - 1) as usize;
+    (Seat::ALL[index % 4], CYCLE[index % 16])
+}
+
+fn sort_hand(cards: &mut [Card]) {
+    cards.sort_by(|a, b| b.suit.cmp(&a.suit).then(b.rank.cmp(&a.rank)));
+}
+
+const SUITS_PBN_ORDER: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+fn parse_suit_cards(suit: Suit, ranks: &str, cards: &mut Vec<Card>) -> Result<(), BridgeError> {
+    for code in ranks.chars() {
+        let rank: Rank = code
+            .to_string()
+            .parse()
+            .map_err(|_| BridgeError::Parse(format!("'{}' isn't a rank", code)))?;
+        cards.push(Card::new(rank, suit));
+    }
+    Ok(())
+}
+
+impl BridgeDeal {
+    /// Deals 13 cards each off the top of the deck, starting left of the dealer
+    pub fn deal(deck: &mut Deck, board: u32) -> Result<Self, BridgeError> {
+        let (dealer, vulnerability) = board_conditions(board);
+        let cards = deck.deal(52)?;
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        let mut seat = dealer.next();
+        for card in cards.into_iter().rev() {
+            hands[seat as usize].push(card);
+            seat = seat.next();
+        }
+        BridgeDeal::new(Some(board), dealer, vulnerability, hands)
+    }
+
+    /// Checks the hands are 13 cards each and the whole deck between them
+    pub fn new(
+        board: Option<u32>,
+        dealer: Seat,
+        vulnerability: Vulnerability,
+        mut hands: [Vec<Card>; 4],
+    ) -> Result<Self, BridgeError> {
+        for seat in Seat::ALL {
+            if hands[seat as usize].len() != 13 {
+                return Err(BridgeError::InvalidDeal(format!(
+                    "{} has {} cards",
+                    seat,
+                    hands[seat as usize].len()
+                )));
+            }
+        }
+        let mut all: Vec<Card> = hands.iter().flatten().copied().collect();
+        all.sort();
+        if let Some(pair) = all.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(BridgeError::InvalidDeal(format!("{} is in two hands", pair[0])));
+        }
+        for hand in hands.iter_mut() {
+            sort_hand(hand);
+        }
+        Ok(BridgeDeal {
+            board,
+            dealer,
+            vulnerability,
+            hands,
+        })
+    }
+
+    pub fn hand(&self, seat: Seat) -> &[Card] {
+        &self.hands[seat as usize]
+    }
+
+    /// Ace 4, King 3, Queen 2, Jack 1
+    pub fn high_card_points(&self, seat: Seat) -> u32 {
+        self.hand(seat)
+            .iter()
+            .map(|card| match card.rank {
+                Rank::Ace => 4,
+                Rank::King => 3,
+                Rank::Queen => 2,
+                Rank::Jack => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    // "AKQ" for the hand's cards in one suit
+    fn suit_ranks(&self, seat: Seat, suit: Suit) -> String {
+        self.hand(seat)
+            .iter()
+            .filter(|card| card.suit == suit)
+            .map(|card| card.rank.code())
+            .collect()
+    }
+
+    fn pbn_hand(&self, seat: Seat) -> String {
+        let suits: Vec<String> = SUITS_PBN_ORDER.iter().map(|suit| self.suit_ranks(seat, *suit)).collect();
+        suits.join(".")
+    }
+
+    /// One PBN record with the standard's mandatory tag set, in its order. A deal only knows the
+    /// board, dealer, vulnerability and hands, every other tag is "?" (unknown)
+    pub fn to_pbn(&self) -> String {
+        let mut seat = self.dealer;
+        let mut hands = vec![];
+        for _ in 0..4 {
+            hands.push(self.pbn_hand(seat));
+            seat = seat.next();
+        }
+        let tags = [
+            ("Event", String::from("?")),
+            ("Site", String::from("?")),
+            ("Date", String::from("?")),
+            ("Board", self.board.map_or(String::from("?"), |board| board.to_string())),
+            ("West", String::from("?")),
+            ("North", String::from("?")),
+            ("East", String::from("?")),
+            ("South", String::from("?")),
+            ("Dealer", self.dealer.letter().to_string()),
+            ("Vulnerable", self.vulnerability.pbn().to_string()),
+            ("Deal", format!("{}:{}", self.dealer.letter(), hands.join(" "))),
+            ("Scoring", String::from("?")),
+            ("Declarer", String::from("?")),
+            ("Contract", String::from("?")),
+            ("Result", String::from("?")),
+        ];
+        tags.iter().map(|(tag, value)| format!("[{} \"{}\"]\n", tag, value)).collect()
+    }
+
+    /// One PBN record. Unknown tags, % lines and {comments} are skipped
+    pub fn from_pbn(text: &str) -> Result<Self, BridgeError> {
+        let mut board = None;
+        let mut dealer = None;
+        let mut vulnerability = None;
+        let mut deal = None;
+
+        for line in text.lines().map(str::trim) {
+            if !line.starts_with('[') {
+                continue;
+            }
+            let inner = line.trim_start_matches('[').trim_end_matches(']');
+            let (tag, value) = inner
+                .split_once(' ')
+                .ok_or_else(|| BridgeError::Parse(format!("bad tag line '{}'", line)))?;
+            let value = value.trim().trim_matches('"');
+            match tag {
+                "Board" => board = value.parse().ok(),
+                "Dealer" => dealer = Some(value.parse::<Seat>()?),
+                "Vulnerable" => vulnerability = Some(Vulnerability::from_pbn(value)?),
+                "Deal" => deal = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        let deal = deal.ok_or(BridgeError::Parse(String::from("no Deal tag")))?;
+        let (first, hands_text) = deal
+            .split_once(':')
+            .ok_or_else(|| BridgeError::Parse(format!("Deal '{}' should start with a seat and ':'", deal)))?;
+        let mut seat: Seat = first.parse()?;
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        let hand_texts: Vec<&str> = hands_text.split_whitespace().collect();
+        if hand_texts.len() != 4 {
+            return Err(BridgeError::Parse(String::from("Deal needs four hands")));
+        }
+        for hand_text in hand_texts {
+            let suits: Vec<&str> = hand_text.split('.').collect();
+            if suits.len() != 4 {
+                return Err(BridgeError::Parse(format!("'{}' should have four suits", hand_text)));
+            }
+            for (suit, ranks) in SUITS_PBN_ORDER.iter().zip(suits) {
+                parse_suit_cards(*suit, ranks, &mut hands[seat as usize])?;
+            }
+            seat = seat.next();
+        }
+
+        // Without the tags the board number still gives dealer and vulnerability
+        let (board_dealer, board_vulnerability) = board_conditions(board.unwrap_or(1));
+        BridgeDeal::new(
+            board,
+            dealer.unwrap_or(board_dealer),
+            vulnerability.unwrap_or(board_vulnerability),
+            hands,
+        )
+    }
+
+    fn lin_hand(&self, seat: Seat) -> String {
+        SUITS_PBN_ORDER
+            .iter()
+            .map(|suit| format!("{}{}", suit.letter(), self.suit_ranks(seat, *suit)))
+            .collect()
+    }
+
+    pub fn to_lin(&self) -> String {
+        let hands: Vec<String> = [Seat::South, Seat::West, Seat::North, Seat::East]
+            .iter()
+            .map(|seat| self.lin_hand(*seat))
+            .collect();
+        let mut text = format!("md|{}{}|sv|{}|", self.dealer.lin_number(), hands.join(","), self.vulnerability.lin());
+        if let Some(board) = self.board {
+            text.push_str(&format!("ah|Board {}|", board));
+        }
+        text
+    }
+
+    pub fn from_lin(text: &str) -> Result<Self, BridgeError> {
+        let fields: Vec<&str> = text.trim().split('|').collect();
+        let value = |key: &str| fields.chunks(2).find(|pair| pair[0] == key).and_then(|pair| pair.get(1).copied());
+
+        let md = value("md").ok_or(BridgeError::Parse(String::from("no md field")))?;
+        let mut chars = md.chars();
+        let dealer = match chars.next() {
+            Some('1') => Seat::South,
+            Some('2') => Seat::West,
+            Some('3') => Seat::North,
+            Some('4') => Seat::East,
+            _ => return Err(BridgeError::Parse(format!("md '{}' should start with the dealer, 1 to 4", md))),
+        };
+
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        let order = [Seat::South, Seat::West, Seat::North, Seat::East];
+        for (seat, hand_text) in order.iter().zip(chars.as_str().split(',')) {
+            let mut suit = None;
+            for code in hand_text.chars() {
+                if let Ok(next) = code.to_string().parse::<Suit>() {
+                    suit = Some(next);
+                    continue;
+                }
+                let suit = suit.ok_or_else(|| BridgeError::Parse(format!("'{}' needs a suit letter first", hand_text)))?;
+                parse_suit_cards(suit, &code.to_string(), &mut hands[*seat as usize])?;
+            }
+        }
+        // The fourth hand is often left out, it's whatever is left
+        if hands[Seat::East as usize].is_empty() {
+            let dealt: Vec<Card> = hands.iter().flatten().copied().collect();
+            hands[Seat::East as usize] =
+                Deck::new().into_cards().into_iter().filter(|card| !dealt.contains(card)).collect();
+        }
+
+        let board = value("ah").and_then(|name| name.trim_start_matches("Board").trim().parse().ok());
+        let vulnerability = match value("sv") {
+            Some(text) => Vulnerability::from_lin(text)?,
+            None => board_conditions(board.unwrap_or(1)).1,
+        };
+        BridgeDeal::new(board, dealer, vulnerability, hands)
+    }
+}
+
+/// A compass diagram like the ones printed in bridge columns
+impl fmt::Display for BridgeDeal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = |seat: Seat| -> Vec<String> {
+            SUITS_PBN_ORDER
+                .iter()
+                .map(|suit| format!("{} {}", suit.letter(), self.suit_ranks(seat, *suit)).trim_end().to_string())
+                .collect()
+        };
+        if let Some(board) = self.board {
+            write!(f, "Board {}, ", board)?;
+        }
+        writeln!(f, "{} deals, vulnerable: {}", self.dealer, self.vulnerability.pbn())?;
+        for line in lines(Seat::North) {
+            writeln!(f, "{:20}{}", "", line)?;
+        }
+        for (west, east) in lines(Seat::West).iter().zip(lines(Seat::East)) {
+            writeln!(f, "{:40}{}", west, east)?;
+        }
+        for line in lines(Seat::South) {
+            writeln!(f, "{:20}{}", "", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every record in a PBN file, records are separated by blank lines
+pub fn read_pbn(text: &str) -> Result<Vec<BridgeDeal>, BridgeError> {
+    let normalised = text.replace("\r\n", "\n");
+    normalised
+        .split("\n\n")
+        .filter(|record| record.lines().any(|line| line.trim_start().starts_with("[Deal ")))
+        .map(BridgeDeal::from_pbn)
+        .collect()
+}
+
+pub fn write_pbn(deals: &[BridgeDeal]) -> String {
+    let records: Vec<String> = deals.iter().map(BridgeDeal::to_pbn).collect();
+    records.join("\n")
+}
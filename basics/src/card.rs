@@ -0,0 +1,256 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Card model
+- Enums instead of strings, so "Hearts" vs "hearts" typos can't happen
+- Derived ordering follows declaration order: Clubs < Diamonds < Hearts < Spades, Two < ... < Ace
+- Cards print long ("Ace of Spades") with {} and short ("AS") with code()
+- Parsing accepts either form
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Suit::Clubs => "Clubs",
+            Suit::Diamonds => "Diamonds",
+            Suit::Hearts => "Hearts",
+            Suit::Spades => "Spades",
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        self.name().chars().next().unwrap()
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Suit::ALL
+            .into_iter()
+            .find(|suit| {
+                suit.name().eq_ignore_ascii_case(text)
+                    || (text.len() == 1 && text.eq_ignore_ascii_case(&suit.letter().to_string()))
+            })
+            .ok_or_else(|| ParseCardError::new(text))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    Two = 2,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// 2 for Two up to 14 for Ace
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn from_value(value: u8) -> Option<Rank> {
+        Rank::ALL.get((value as usize).checked_sub(2)?).copied()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "T",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        }
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // "10" is how most people write the short form of Ten
+        let text = if text == "10" { "T" } else { text };
+
+        Rank::ALL
+            .into_iter()
+            .find(|rank| rank.name().eq_ignore_ascii_case(text) || rank.code().eq_ignore_ascii_case(text))
+            .ok_or_else(|| ParseCardError::new(text))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Card {
+    pub suit: Suit,
+    pub rank: Rank,
+}
+
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card { suit, rank }
+    }
+
+    /// Two character form e.g. "AS", "TD"
+    pub fn code(&self) -> String {
+        format!("{}{}", self.rank.code(), self.suit.letter())
+    }
+
+    /// 0 to 51, the card's place in a new deck (2C is 0, AS is 51). Fits in one byte
+    pub fn index(&self) -> u8 {
+        self.suit as u8 * 13 + (self.rank.value() - 2)
+    }
+
+    pub fn from_index(index: u8) -> Option<Card> {
+        if index >= 52 {
+            return None;
+        }
+        let rank = Rank::from_value(index % 13 + 2)?;
+        Some(Card::new(rank, Suit::ALL[(index / 13) as usize]))
+    }
+}
+
+// Saved as the short code, "AS", so JSON saves stay readable
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} of {}", self.rank, self.suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let invalid = || ParseCardError::new(text);
+
+        // Long form: "Ace of Spades"
+        if let Some((rank, suit)) = text.split_once(" of ") {
+            let rank = rank.trim().parse().map_err(|_| invalid())?;
+            let suit = suit.trim().parse().map_err(|_| invalid())?;
+            return Ok(Card::new(rank, suit));
+        }
+
+        // Short form: "AS", "10S". The suit is always the last character
+        let mut chars = text.chars();
+        let suit = chars.next_back().ok_or_else(invalid)?;
+        if chars.as_str().is_empty() {
+            return Err(invalid());
+        }
+        let rank = chars.as_str().parse().map_err(|_| invalid())?;
+        let suit = suit.to_string().parse().map_err(|_| invalid())?;
+        Ok(Card::new(rank, suit))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseCardError {
+    input: String,
+}
+
+impl ParseCardError {
+    fn new(input: &str) -> Self {
+        ParseCardError {
+            input: input.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a card, expected something like \"Ace of Spades\" or \"AS\"",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseCardError {}
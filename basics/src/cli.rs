@@ -0,0 +1,58 @@
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Command line flags for the bins, `--name value`
+
+- A flag that's there but can't be read is an error, never quietly the default. A run with
+  `--seed 12x` would otherwise report seed 1 as if that's what was asked for
+- usage_error() prints the reason and exits with 2, the usual code for bad arguments
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlagError {
+    MissingValue(String),
+    Invalid { flag: String, value: String, reason: String },
+}
+
+impl fmt::Display for FlagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlagError::MissingValue(flag) => write!(f, "{} needs a value", flag),
+            FlagError::Invalid { flag, value, reason } => write!(f, "'{}' isn't a valid {}: {}", value, flag, reason),
+        }
+    }
+}
+
+impl std::error::Error for FlagError {}
+
+/// The flag's value, None when the flag isn't given
+pub fn optional_flag<T>(args: &[String], name: &str) -> Result<Option<T>, FlagError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    let value = args.get(index + 1).ok_or_else(|| FlagError::MissingValue(name.to_string()))?;
+    value.parse().map(Some).map_err(|reason: T::Err| FlagError::Invalid {
+        flag: name.to_string(),
+        value: value.clone(),
+        reason: reason.to_string(),
+    })
+}
+
+/// The flag's value, `default` when the flag isn't given
+pub fn flag<T>(args: &[String], name: &str, default: T) -> Result<T, FlagError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    Ok(optional_flag(args, name)?.unwrap_or(default))
+}
+
+/// Prints the reason and exits with status 2
+pub fn usage_error(reason: impl fmt::Display) -> ! {
+    eprintln!("{}", reason);
+    std::process::exit(2)
+}
@@ -0,0 +1,178 @@
+use super::card::Card;
+use super::deck::Deck;
+use rand::rngs::OsRng;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Verifiable shuffles (commit-reveal)
+
+- secure_shuffle() shuffles with ChaCha20 seeded by the operating system, a cryptographically secure rng.
+  Unlike a seeded deck there's no seed anyone could know or choose
+- Before dealing, the dealer publishes the Commitment: sha256 of a random salt and the deck order
+- After the hand the dealer publishes the Reveal, the salt and the order. verify() checks the
+  order is one whole 52 card deck, the hash matches and every deal came off the top of the committed order
+- Without the salt a player could check guesses about the order against the hash before the reveal
+
+Reveal text, order is bottom of the deck first like Deck::cards():
+  salt 3f9a...
+  order 7H 2C ... AS
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment(String); // sha256 as hex
+
+impl Commitment {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = VerifyError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim().to_ascii_lowercase();
+        if text.len() != 64 || !text.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(VerifyError::Malformed(String::from(
+                "a commitment is 64 hex characters",
+            )));
+        }
+        Ok(Commitment(text))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reveal {
+    pub salt: String, // hex
+    pub order: Vec<Card>,
+}
+
+impl Reveal {
+    pub fn commitment(&self) -> Commitment {
+        let codes: Vec<String> = self.order.iter().map(|card| card.code()).collect();
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}\n{}", self.salt, codes.join(" ")).as_bytes());
+        Commitment(hex(&hasher.finalize()))
+    }
+}
+
+impl fmt::Display for Reveal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let codes: Vec<String> = self.order.iter().map(|card| card.code()).collect();
+        writeln!(f, "salt {}", self.salt)?;
+        writeln!(f, "order {}", codes.join(" "))
+    }
+}
+
+impl FromStr for Reveal {
+    type Err = VerifyError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut salt = None;
+        let mut order = None;
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match line.split_once(' ') {
+                Some(("salt", value)) => salt = Some(value.trim().to_string()),
+                Some(("order", value)) => {
+                    let cards = value
+                        .split_whitespace()
+                        .map(|code| code.parse::<Card>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|reason| VerifyError::Malformed(reason.to_string()))?;
+                    order = Some(cards);
+                }
+                _ => return Err(VerifyError::Malformed(format!("unexpected line '{}'", line))),
+            }
+        }
+        match (salt, order) {
+            (Some(salt), Some(order)) => Ok(Reveal { salt, order }),
+            _ => Err(VerifyError::Malformed(String::from(
+                "a reveal needs a salt line and an order line",
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError {
+    Malformed(String),
+    HashMismatch,
+    DuplicateCard(Card),
+    MissingCards(Vec<Card>),
+    DealtMismatch { deal: usize }, // Counting from 1
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::Malformed(reason) => write!(f, "malformed: {}", reason),
+            VerifyError::HashMismatch => write!(f, "the revealed deck doesn't match the commitment"),
+            VerifyError::DuplicateCard(card) => write!(f, "the revealed deck has {} twice", card),
+            VerifyError::MissingCards(cards) => {
+                let codes: Vec<String> = cards.iter().map(|card| card.code()).collect();
+                write!(f, "the revealed deck is missing {}", codes.join(" "))
+            }
+            VerifyError::DealtMismatch { deal } => {
+                write!(f, "deal {} wasn't the next cards off the committed deck", deal)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Shuffles with a CSPRNG and commits to the result. Publish the Commitment now, the Reveal after the hand.
+/// Only a full deck's reveal verifies, so shuffle before dealing anything
+pub fn secure_shuffle(deck: &mut Deck) -> (Commitment, Reveal) {
+    deck.shuffle_with(&mut ChaCha20Rng::from_entropy());
+
+    let mut salt = [0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+    let reveal = Reveal {
+        salt: hex(&salt),
+        order: deck.cards().to_vec(),
+    };
+    (reveal.commitment(), reveal)
+}
+
+/// Checks the reveal is exactly one standard deck and matches the commitment, then that `deals`
+/// (each Deck::deal result, in order) were dealt from the top of the revealed order
+pub fn verify(commitment: &Commitment, reveal: &Reveal, deals: &[Vec<Card>]) -> Result<(), VerifyError> {
+    let mut seen = HashSet::new();
+    for card in &reveal.order {
+        if !seen.insert(card) {
+            return Err(VerifyError::DuplicateCard(*card));
+        }
+    }
+    let missing: Vec<Card> = Deck::new().into_cards().into_iter().filter(|card| !seen.contains(card)).collect();
+    if !missing.is_empty() {
+        return Err(VerifyError::MissingCards(missing));
+    }
+
+    if reveal.commitment() != *commitment {
+        return Err(VerifyError::HashMismatch);
+    }
+
+    let mut deck = Deck::from_cards(reveal.order.clone());
+    for (index, dealt) in deals.iter().enumerate() {
+        match deck.deal(dealt.len()) {
+            Ok(cards) if cards == *dealt => {}
+            _ => return Err(VerifyError::DealtMismatch { deal: index + 1 }),
+        }
+    }
+    Ok(())
+}
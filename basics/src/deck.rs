@@ -0,0 +1,269 @@
+use super::card::{Card, Rank, Suit};
+use super::odds::{self, Odds};
+use super::shuffles::Technique;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DealError {
+    NotEnoughCards { requested: usize, remaining: usize },
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DealError::NotEnoughCards { requested, remaining } => write!(
+                f,
+                "can't deal {} cards, only {} left in the deck",
+                requested, remaining
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DealError {}
+
+/*
+The top of the deck is the end of the vector, dealing takes cards off the end
+
+Seeded decks
+- A seeded shuffle is the Fisher-Yates at the bottom of this file, fed by ChaCha8Rng. rand_chacha keeps
+  ChaCha8's output the same on every platform and version, and the shuffle on top of it is ours, so a seed
+  deals the same cards whatever rand version is built. rand's own SliceRandom::shuffle doesn't promise that
+- Once seeded, every later shuffle (including reshuffling the discards) continues the same sequence,
+  until shuffle_with() brings in an rng the deck doesn't know, which drops the seed
+
+Card type
+- Deck<C> holds any card type, plain `Deck` means the standard 52 card Deck<Card>
+- Deck::new() and with_seed() build the standard deck, from_cards() builds any other (see DeckSpec),
+  from_cards_seeded() a seeded one, or collect() straight from an iterator
+- Shuffling, dealing, discarding and reshuffling work the same whatever the card type. Odds and counts
+  only make sense for standard cards so they're only on Deck<Card>
+ */
+#[derive(Debug, Clone)]
+pub struct Deck<C = Card> {
+    cards: Vec<C>, //Vectors---- like arrays but can change size
+    discards: Vec<C>,
+    seed: Option<u64>,
+    rng: Option<ChaCha8Rng>,
+    whole: bool, // Started as one whole standard deck, so every card not in it has been seen
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        /* Inherit implementaiton a.k.a. Add a function to a struct....can define methods and functions
+        - Associated functions ---- not tied to an instance
+        - Methods -----tied to an instance
+
+        */
+        //Arrays vs Vectors performance difference is tiny and is a matter of communication
+        //Suit::ALL and Rank::ALL are arrays, the deck is a vector because dealing shrinks it
+
+        /*
+        - Variables are immutable by default. Technical an immutable variable is called a Binding
+         */
+        let mut cards = vec![];
+
+        for suit in Suit::ALL {
+            for rank in Rank::ALL {
+                cards.push(Card::new(rank, suit));
+            }
+        }
+        // println!("Heres your deck: {:?}", cards);
+        // return Deck { cards };
+        // return deck;
+        // deck
+        let mut deck = Deck::from_cards(cards);
+        deck.whole = true;
+        deck //Implicit return - auto return the last expression
+    }
+
+    /// A new, unshuffled deck whose shuffles all come from `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        let mut deck = Deck::new();
+        deck.reseed(seed);
+        deck
+    }
+
+    /// Counts and chances for the cards still to be dealt
+    pub fn odds(&self) -> Odds {
+        Odds::new(&self.cards)
+    }
+
+    /// Hi-Lo running count, taking every card no longer in the deck as seen. None unless the deck
+    /// started as one whole standard deck (new(), with_seed() or a loaded save), otherwise the cards
+    /// it never had would count as seen. For any other deck use odds::running_count() on the cards dealt
+    pub fn running_count(&self) -> Option<i32> {
+        self.whole.then(|| -self.odds().hi_lo_total())
+    }
+
+    /// Running count per deck left, None when running_count() is
+    pub fn true_count(&self) -> Option<f64> {
+        self.running_count().map(|count| odds::true_count(count, self.cards.len()))
+    }
+}
+
+impl<C> Deck<C> {
+    /// A deck of any cards, in the order given. The last card is the top
+    pub fn from_cards(cards: Vec<C>) -> Self {
+        Deck {
+            cards,
+            discards: vec![],
+            seed: None,
+            rng: None,
+            whole: false,
+        }
+    }
+
+    /// Same as from_cards() but every shuffle comes from `seed`
+    pub fn from_cards_seeded(cards: Vec<C>, seed: u64) -> Self {
+        let mut deck = Deck::from_cards(cards);
+        deck.reseed(seed);
+        deck
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = Some(ChaCha8Rng::seed_from_u64(seed));
+    }
+
+    pub fn shuffle(&mut self) {
+        shuffle_cards(&mut self.cards, self.rng.as_mut());
+    }
+
+    /// Records the seed and shuffles from it
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        self.reseed(seed);
+        self.shuffle();
+    }
+
+    /// Shuffles with the caller's rng. The deck can't record a seed it never saw, so it forgets any seed it
+    /// had: the order no longer follows from it, and later shuffles shouldn't be predictable from it either
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.seed = None;
+        self.rng = None;
+        self.cards.shuffle(rng);
+    }
+
+    /// A riffle, overhand or cut like a person would do it, from the deck's rng if it's seeded
+    pub fn shuffle_by(&mut self, technique: Technique) {
+        match self.rng.as_mut() {
+            Some(rng) => technique.apply(&mut self.cards, rng),
+            None => technique.apply(&mut self.cards, &mut thread_rng()),
+        }
+    }
+
+    pub fn deal(&mut self, num_cards: usize) -> Result<Vec<C>, DealError> { //TODO: See diagram for list of number types in Rust
+        // Without this check len() - num_cards underflows and panics
+        if num_cards > self.cards.len() {
+            return Err(DealError::NotEnoughCards {
+                requested: num_cards,
+                remaining: self.cards.len(),
+            });
+        }
+        Ok(self.cards.split_off(self.cards.len() - num_cards))
+    }
+
+    /// Deals as many as it can, possibly fewer than asked for
+    pub fn deal_up_to(&mut self, num_cards: usize) -> Vec<C> {
+        let num_cards = num_cards.min(self.cards.len());
+        self.cards.split_off(self.cards.len() - num_cards)
+    }
+
+    pub fn draw_one(&mut self) -> Option<C> {
+        self.cards.pop()
+    }
+
+    /// The card draw_one would return, without taking it
+    pub fn peek(&self) -> Option<&C> {
+        self.cards.last()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn cards(&self) -> &[C] {
+        &self.cards
+    }
+
+    pub fn into_cards(self) -> Vec<C> {
+        self.cards
+    }
+
+    pub fn discard(&mut self, cards: Vec<C>) {
+        self.discards.extend(cards);
+    }
+
+    pub fn discards(&self) -> &[C] {
+        &self.discards
+    }
+
+    /// Shuffles the discard pile and puts it under the cards still in the deck
+    pub fn reshuffle_discards(&mut self) {
+        shuffle_cards(&mut self.discards, self.rng.as_mut());
+        self.discards.append(&mut self.cards);
+        std::mem::swap(&mut self.cards, &mut self.discards);
+    }
+
+    // For saving and loading, the rng is saved so a seeded deck carries on where it left off
+    pub(crate) fn rng(&self) -> Option<&ChaCha8Rng> {
+        self.rng.as_ref()
+    }
+
+    // Only for cards that were checked to be one whole deck, along with any hands
+    pub(crate) fn from_parts(cards: Vec<C>, discards: Vec<C>, seed: Option<u64>, rng: Option<ChaCha8Rng>) -> Self {
+        Deck {
+            cards,
+            discards,
+            seed,
+            rng,
+            whole: true,
+        }
+    }
+}
+
+impl<C> FromIterator<C> for Deck<C> {
+    fn from_iter<I: IntoIterator<Item = C>>(cards: I) -> Self {
+        Deck::from_cards(cards.into_iter().collect())
+    }
+}
+
+// Seeded decks (and shoes) shuffle from their own rng, everything else from thread_rng()
+pub(crate) fn shuffle_cards<C>(cards: &mut [C], rng: Option<&mut ChaCha8Rng>) {
+    match rng {
+        Some(rng) => seeded_shuffle(cards, rng),
+        None => cards.shuffle(&mut thread_rng()),
+    }
+}
+
+// Fisher-Yates, swapping each position from the top down with a uniformly chosen one at or below it
+fn seeded_shuffle<C>(cards: &mut [C], rng: &mut ChaCha8Rng) {
+    for top in (1..cards.len()).rev() {
+        let other = below(rng, top as u64 + 1) as usize;
+        cards.swap(top, other);
+    }
+}
+
+// Uniform in 0..bound. Draws past the last whole multiple of bound would favour small values so they're redrawn
+fn below(rng: &mut ChaCha8Rng, bound: u64) -> u64 {
+    let zone = u64::MAX - u64::MAX % bound;
+    loop {
+        let value = rng.next_u64();
+        if value < zone {
+            return value % bound;
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use super::deck::Deck;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/**
+Deck definitions for games that don't use the standard 52 cards
+
+- Built in code with the DeckSpec builder methods, or read from a small config file
+- Every suit gets every rank, `copies` repeats the whole set (pinochle is two copies)
+- `name*3` repeats one rank or extra card three times (Uno has one 0 but two of each 1-9)
+- Extras are cards with no suit: jokers, tarot trumps, Uno wilds
+- `card` is how a card's name is built from its suit and rank, "{rank} of {suit}" unless set
+
+Config file, one `key = value` per line, # starts a comment, extra can repeat:
+
+  name = Uno
+  suits = Red, Yellow, Green, Blue
+  ranks = 0, 1*2, 2*2, 3*2, 4*2, 5*2, 6*2, 7*2, 8*2, 9*2, Skip*2, Reverse*2, Draw Two*2
+  extra = Wild*4
+  extra = Wild Draw Four*4
+  card = {suit} {rank}
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CustomCard {
+    pub suit: Option<String>, // None for extras
+    pub rank: String,
+    pub name: String,
+}
+
+impl fmt::Display for CustomCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecError {
+    pub line: usize, // 0 when the problem isn't on a particular line
+    pub reason: String,
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.reason)
+        } else {
+            write!(f, "line {}: {}", self.line, self.reason)
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckSpec {
+    pub name: String,
+    pub suits: Vec<String>,
+    pub ranks: Vec<(String, usize)>,  // Rank and how many of it per suit
+    pub extras: Vec<(String, usize)>, // Unsuited card and how many of it
+    pub copies: usize,
+    pub card_name: String,
+}
+
+impl DeckSpec {
+    pub fn new(name: &str) -> Self {
+        DeckSpec {
+            name: name.to_string(),
+            suits: vec![],
+            ranks: vec![],
+            extras: vec![],
+            copies: 1,
+            card_name: String::from("{rank} of {suit}"),
+        }
+    }
+
+    pub fn suits(mut self, suits: &[&str]) -> Self {
+        self.suits = suits.iter().map(|suit| suit.to_string()).collect();
+        self
+    }
+
+    pub fn ranks(mut self, ranks: &[&str]) -> Self {
+        self.ranks = ranks.iter().map(|rank| (rank.to_string(), 1)).collect();
+        self
+    }
+
+    pub fn rank(mut self, rank: &str, count: usize) -> Self {
+        self.ranks.push((rank.to_string(), count));
+        self
+    }
+
+    pub fn extra(mut self, name: &str, count: usize) -> Self {
+        self.extras.push((name.to_string(), count));
+        self
+    }
+
+    pub fn jokers(self, count: usize) -> Self {
+        self.extra("Joker", count)
+    }
+
+    pub fn copies(mut self, copies: usize) -> Self {
+        self.copies = copies;
+        self
+    }
+
+    pub fn card_name(mut self, template: &str) -> Self {
+        self.card_name = template.to_string();
+        self
+    }
+
+    pub fn size(&self) -> usize {
+        let per_suit: usize = self.ranks.iter().map(|(_, count)| count).sum();
+        let extras: usize = self.extras.iter().map(|(_, count)| count).sum();
+        (self.suits.len() * per_suit + extras) * self.copies
+    }
+
+    /// Every card the spec describes, suits in order then extras
+    pub fn cards(&self) -> Vec<CustomCard> {
+        let mut cards = vec![];
+        for _ in 0..self.copies {
+            for suit in &self.suits {
+                for (rank, count) in &self.ranks {
+                    let name = self.card_name.replace("{rank}", rank).replace("{suit}", suit);
+                    for _ in 0..*count {
+                        cards.push(CustomCard {
+                            suit: Some(suit.clone()),
+                            rank: rank.clone(),
+                            name: name.clone(),
+                        });
+                    }
+                }
+            }
+            for (extra, count) in &self.extras {
+                for _ in 0..*count {
+                    cards.push(CustomCard {
+                        suit: None,
+                        rank: extra.clone(),
+                        name: extra.clone(),
+                    });
+                }
+            }
+        }
+        cards
+    }
+
+    /// An unshuffled deck with the same shuffle/deal API as the standard deck
+    pub fn build(&self) -> Result<Deck<CustomCard>, SpecError> {
+        if self.size() == 0 {
+            return Err(SpecError {
+                line: 0,
+                reason: format!("deck '{}' has no cards", self.name),
+            });
+        }
+        Ok(Deck::from_cards(self.cards()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SpecError> {
+        let text = fs::read_to_string(path).map_err(|err| SpecError {
+            line: 0,
+            reason: format!("{}: {}", path.display(), err),
+        })?;
+        text.parse()
+    }
+}
+
+// "Skip*2" -> ("Skip", 2), "Ace" -> ("Ace", 1)
+fn counted(item: &str, line: usize) -> Result<(String, usize), SpecError> {
+    match item.rsplit_once('*') {
+        Some((name, count)) => {
+            let count = count.trim().parse().map_err(|_| SpecError {
+                line,
+                reason: format!("'{}' should be name*count", item),
+            })?;
+            Ok((name.trim().to_string(), count))
+        }
+        None => Ok((item.trim().to_string(), 1)),
+    }
+}
+
+fn list(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+impl FromStr for DeckSpec {
+    type Err = SpecError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut spec = DeckSpec::new("Custom");
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(SpecError {
+                line: line_number,
+                reason: format!("expected 'key = value', got '{}'", line),
+            })?;
+            let value = value.trim();
+            let number = || {
+                value.parse::<usize>().map_err(|_| SpecError {
+                    line: line_number,
+                    reason: format!("'{}' is not a number", value),
+                })
+            };
+
+            match key.trim() {
+                "name" => spec.name = value.to_string(),
+                "suits" => spec.suits = list(value).iter().map(|suit| suit.to_string()).collect(),
+                "ranks" => {
+                    spec.ranks = list(value)
+                        .iter()
+                        .map(|rank| counted(rank, line_number))
+                        .collect::<Result<_, _>>()?
+                }
+                "extra" => spec.extras.push(counted(value, line_number)?),
+                "jokers" => spec.extras.push((String::from("Joker"), number()?)),
+                "copies" => spec.copies = number()?,
+                "card" => spec.card_name = value.to_string(),
+                other => {
+                    return Err(SpecError {
+                        line: line_number,
+                        reason: format!("unknown key '{}'", other),
+                    })
+                }
+            }
+        }
+
+        Ok(spec)
+    }
+}
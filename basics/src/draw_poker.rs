@@ -0,0 +1,254 @@
+use super::card::{Card, Rank};
+use super::deck::Deck;
+use super::hand_history::HandHistory;
+use super::poker::{self, Category};
+use super::table::{CardGame, Progress, Table, TableError};
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Five card draw, without betting rounds
+
+- Everyone antes and gets five cards, then in turn each player draws (up to three cards) or folds
+- After the last player has drawn the best poker hand takes the pot, equal hands split it
+- At most six players: 30 cards dealt plus 18 drawn fits in one deck
+- Bots (DrawBot) make the one decision from their own five cards. heads_up() plays two of them
+  against each other on a seeded deck, the same seed replays the same match, and records every hand
+  as a hand history
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrawAction {
+    Draw(Vec<usize>), // Positions in the hand to throw away, empty to stand pat
+    Fold,
+}
+
+impl fmt::Display for DrawAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawAction::Draw(positions) if positions.is_empty() => write!(f, "stand"),
+            DrawAction::Draw(positions) => {
+                let positions: Vec<String> = positions.iter().map(|position| position.to_string()).collect();
+                write!(f, "draw {}", positions.join(" "))
+            }
+            DrawAction::Fold => write!(f, "fold"),
+        }
+    }
+}
+
+/// "stand", "fold" or "draw 0 3 4"
+impl FromStr for DrawAction {
+    type Err = TableError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut words = text.split_whitespace();
+        match words.next() {
+            Some("stand") => Ok(DrawAction::Draw(vec![])),
+            Some("fold") => Ok(DrawAction::Fold),
+            Some("draw") => words
+                .map(|word| word.parse())
+                .collect::<Result<Vec<usize>, _>>()
+                .map(DrawAction::Draw)
+                .map_err(|_| TableError::IllegalAction(String::from("draw takes card positions 0-4"))),
+            _ => Err(TableError::IllegalAction(format!(
+                "'{}' isn't stand, fold or draw",
+                text.trim()
+            ))),
+        }
+    }
+}
+
+pub const MAX_DRAW: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct DrawPoker {
+    pub ante: i64,
+    to_act: usize,
+}
+
+impl DrawPoker {
+    pub fn new(ante: i64) -> Self {
+        DrawPoker { ante, to_act: 0 }
+    }
+}
+
+impl CardGame for DrawPoker {
+    type Action = DrawAction;
+
+    fn name(&self) -> String {
+        format!("5 Card Draw (ante {})", self.ante)
+    }
+
+    fn max_players(&self) -> usize {
+        6
+    }
+
+    fn start_hand(&mut self, table: &mut Table) -> Result<(), TableError> {
+        for seat in table.in_hand() {
+            table.ante(seat, self.ante)?;
+        }
+        table.street("DEALING HANDS", vec![]);
+        table.deal_round_robin(5)?;
+        table.street("FIRST DRAW", vec![]);
+        self.to_act = table.in_hand().len();
+        Ok(())
+    }
+
+    fn act(&mut self, table: &mut Table, seat: usize, action: DrawAction) -> Result<Progress, TableError> {
+        match action {
+            DrawAction::Fold => table.fold(seat)?,
+            DrawAction::Draw(positions) => {
+                if positions.len() > MAX_DRAW {
+                    return Err(TableError::IllegalAction(format!(
+                        "can draw at most {} cards",
+                        MAX_DRAW
+                    )));
+                }
+                let before = table.player(seat)?.hand.len();
+                table.discard_from(seat, &positions)?;
+                let thrown = before - table.player(seat)?.hand.len();
+                table.deal_to(seat, thrown)?;
+            }
+        }
+
+        self.to_act -= 1;
+        Ok(if self.to_act == 0 {
+            Progress::HandOver
+        } else {
+            Progress::NextPlayer
+        })
+    }
+
+    fn winners(&self, table: &Table, contenders: &[usize]) -> Vec<usize> {
+        let ranked: Vec<(usize, poker::HandRank)> = contenders
+            .iter()
+            .copied()
+            .filter_map(|seat| {
+                let hand = &table.player(seat).ok()?.hand;
+                poker::best_of(hand).map(|rank| (seat, rank))
+            })
+            .collect();
+        let best = ranked.iter().map(|(_, rank)| *rank).max();
+        ranked
+            .into_iter()
+            .filter(|(_, rank)| Some(*rank) == best)
+            .map(|(seat, _)| seat)
+            .collect()
+    }
+}
+
+/// The player's hand, for showing at the table
+pub fn describe_hand(cards: &[Card]) -> String {
+    let codes: Vec<String> = cards.iter().map(|card| card.code()).collect();
+    match poker::best_of(cards) {
+        Some(rank) => format!("{} - {}", codes.join(" "), rank.category()),
+        None => codes.join(" "),
+    }
+}
+
+/// How a draw poker bot decides to draw or fold
+pub trait DrawBot {
+    fn choose(&self, hand: &[Card]) -> DrawAction;
+}
+
+/// Never draws
+pub struct StandPat;
+
+impl DrawBot for StandPat {
+    fn choose(&self, _hand: &[Card]) -> DrawAction {
+        DrawAction::Draw(vec![])
+    }
+}
+
+/// Stands on a straight or better, otherwise keeps its pairs (or two highest cards) and draws the rest
+pub struct KeepGroups;
+
+impl DrawBot for KeepGroups {
+    fn choose(&self, hand: &[Card]) -> DrawAction {
+        DrawAction::Draw(throw_away(hand))
+    }
+}
+
+/// Plays like KeepGroups but folds anything below `min`
+pub struct Tight {
+    pub min: Category,
+}
+
+impl DrawBot for Tight {
+    fn choose(&self, hand: &[Card]) -> DrawAction {
+        match poker::best_of(hand) {
+            Some(rank) if rank.category() >= self.min => DrawAction::Draw(throw_away(hand)),
+            _ => DrawAction::Fold,
+        }
+    }
+}
+
+// Positions KeepGroups throws away
+fn throw_away(hand: &[Card]) -> Vec<usize> {
+    if poker::best_of(hand).is_some_and(|rank| rank.category() >= Category::Straight) {
+        return vec![];
+    }
+    let count = |rank: Rank| hand.iter().filter(|card| card.rank == rank).count();
+    let mut unpaired: Vec<usize> = (0..hand.len()).filter(|position| count(hand[*position].rank) == 1).collect();
+    if unpaired.len() == hand.len() {
+        // Nothing paired, keep the two highest
+        unpaired.sort_by_key(|position| hand[*position].rank);
+        unpaired.truncate(MAX_DRAW);
+    }
+    unpaired
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadsUp {
+    pub chips: [i64; 2], // First bot's chips, second bot's chips
+    pub hands_played: usize,
+    pub histories: Vec<HandHistory>,
+}
+
+/// Two named bots play up to `hands` hands, or until one runs out of chips. The first sits in seat 0.
+/// Every hand is recorded as a hand history dated `time` (seconds since 1970), so the same arguments
+/// always give the same histories
+pub fn heads_up(
+    players: [(&str, &dyn DrawBot); 2],
+    chips: i64,
+    ante: i64,
+    hands: usize,
+    seed: u64,
+    time: u64,
+) -> HeadsUp {
+    let mut table = Table::new(Deck::with_seed(seed), 2);
+    let mut game = DrawPoker::new(ante);
+    // Names tell players apart in the history
+    let second_name = if players[0].0 == players[1].0 {
+        format!("{} (2)", players[1].0)
+    } else {
+        players[1].0.to_string()
+    };
+    for name in [players[0].0, second_name.as_str()] {
+        table.sit(name, chips).expect("two empty seats");
+    }
+    let mut histories = vec![];
+
+    for number in 1..=hands {
+        // Fails once someone is out of chips
+        if table.start_hand(&mut game).is_err() {
+            break;
+        }
+        while let Some(seat) = table.turn() {
+            let hand = table.player(seat).map(|player| player.hand.clone()).unwrap_or_default();
+            let action = players[seat].1.choose(&hand);
+            // A bot that asks for something illegal folds
+            if table.act(&mut game, seat, action).is_err() {
+                table.act(&mut game, seat, DrawAction::Fold).expect("folding is always allowed");
+            }
+        }
+        let record = table.last_hand().expect("the hand was played out");
+        histories.push(record.to_history(number as u64, &format!("Seed {}", seed), time));
+    }
+
+    let chips_of = |seat: usize| table.player(seat).map_or(0, |player| player.chips);
+    HeadsUp {
+        chips: [chips_of(0), chips_of(1)],
+        hands_played: histories.len(),
+        histories,
+    }
+}
@@ -0,0 +1,768 @@
+use super::card::Card;
+use super::poker;
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Hand histories in the PokerStars text format, the one most review and tracking tools read
+
+  PokerStars Hand #1: 5 Card Draw (ante 5) - 2026/10/19 14:03:51 UTC
+  Table 'Seed 42' 2-max Seat #1 is the button
+  Seat 1: KeepGroups (100 in chips)
+  KeepGroups: posts the ante 5
+  *** DEALING HANDS ***
+  Dealt to KeepGroups [As Ad 9s Td 5c]
+  KeepGroups: discards 3 cards [9s Td 5c]
+  Dealt to KeepGroups [As Ad] [2s 3h 9c]
+  *** SHOW DOWN ***
+  KeepGroups: shows [As Ad 2s 3h 9c] (One Pair)
+  KeepGroups collected 10 from pot
+  *** SUMMARY ***
+  Total pot 10 | Rake 0
+
+- Cards are written the PokerStars way, suit in lower case ("Td"). Anything Card parses is read back
+- A history records what everyone held, not just one player's view: every hand is "Dealt to"
+- Streets (*** FLOP *** [..], *** FIRST DRAW ***, ...) are events, so hold'em boards and draw games
+  both fit. Once there's a board a street shows it before the new cards, *** TURN *** [Qh Jh 2c] [Th].
+  The summary is written for other tools and skipped when reading, it's all in the events
+- "raises 10 to 20" is by 10 to 20 in on the street, what it puts in depends on what the player already had in
+- Times are written and read in UTC. A history in any other time zone is refused rather than read wrong
+- Several hands in one file are separated by blank lines
+- Replayer steps through a hand an event at a time, forwards and back, showing chips, cards and the pot
+- Tables record every hand as Events (see Table::last_hand), so any CardGame can be written out.
+  Event takes any card type for that, a HandHistory holds Cards
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<C = Card> {
+    Ante { player: String, amount: i64 },
+    Blind { player: String, amount: i64, big: bool },
+    Bet { player: String, amount: i64 },
+    Call { player: String, amount: i64 },
+    Raise { player: String, amount: i64, to: i64 },
+    Check { player: String },
+    Fold { player: String },
+    Dealt { player: String, cards: Vec<C> },
+    Draw { player: String, discarded: Vec<C>, drawn: Vec<C> }, // Nothing discarded is standing pat
+    Street { name: String, cards: Vec<C> },                    // Board cards dealt, if any
+    Shows { player: String, cards: Vec<C> },
+    Returned { player: String, amount: i64 }, // An uncalled bet coming back
+    Collected { player: String, amount: i64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeatEntry {
+    pub seat: usize, // From 1, like the format
+    pub name: String,
+    pub chips: i64, // At the start of the hand
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandHistory {
+    pub id: u64,
+    pub game: String, // e.g. "5 Card Draw (ante 5)"
+    pub time: u64,    // Seconds since 1970, UTC
+    pub table: String,
+    pub max_seats: usize,
+    pub button: usize,
+    pub seats: Vec<SeatEntry>,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+fn card_text(card: &Card) -> String {
+    format!("{}{}", card.rank.code(), card.suit.letter().to_ascii_lowercase())
+}
+
+fn cards_text(cards: &[Card]) -> String {
+    let cards: Vec<String> = cards.iter().map(card_text).collect();
+    format!("[{}]", cards.join(" "))
+}
+
+// Days since 1970-01-01 to (year, month, day), and back. Howard Hinnant's civil calendar algorithms
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn time_text(time: u64) -> String {
+    let (year, month, day) = civil_from_days((time / 86_400) as i64);
+    let seconds = time % 86_400;
+    format!(
+        "{}/{:02}/{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// "2026/10/19 14:03:51 UTC". Dates and times that don't exist and other time zones are errors
+fn parse_time(text: &str) -> Result<u64, String> {
+    let invalid = || format!("'{}' isn't a date and time", text);
+    let mut parts = text.split_whitespace();
+    let (Some(date), Some(clock)) = (parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    match (parts.next(), parts.next()) {
+        (Some("UTC"), None) => {}
+        (Some(zone), None) => return Err(format!("'{}' is in {}, only UTC times can be read", text, zone)),
+        _ => return Err(format!("'{}' should end in UTC", text)),
+    }
+    let date: Vec<u32> = date.split('/').map(str::parse).collect::<Result<_, _>>().map_err(|_| invalid())?;
+    let clock: Vec<u64> = clock.split(':').map(str::parse).collect::<Result<_, _>>().map_err(|_| invalid())?;
+    if date.len() != 3 || clock.len() != 3 {
+        return Err(invalid());
+    }
+    if clock[0] > 23 || clock[1] > 59 || clock[2] > 59 {
+        return Err(invalid());
+    }
+    let (year, month, day) = (i64::from(date[0]), date[1], date[2]);
+    if !(1..=12).contains(&month) || day == 0 {
+        return Err(invalid());
+    }
+    // A day past the end of the month would roll over into the next one
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return Err(invalid());
+    }
+    let seconds = u64::try_from(days).map_err(|_| invalid())? * 86_400;
+    Ok(seconds + clock[0] * 3600 + clock[1] * 60 + clock[2])
+}
+
+impl HandHistory {
+    /// Everything put in (antes, blinds, bets, calls and raises) less uncalled bets returned
+    pub fn pot(&self) -> i64 {
+        // A raise only says what it's raising to, so what's already in on the street has to be followed
+        let mut state = ReplayState::start(self);
+        let mut pot = 0;
+        for event in &self.events {
+            if !matches!(event, Event::Collected { .. }) {
+                pot += state.chips_in(event);
+            }
+            state.apply(event);
+        }
+        pot
+    }
+
+    /// Every line of the text format, summary included
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("PokerStars Hand #{}: {} - {}", self.id, self.game, time_text(self.time)),
+            format!(
+                "Table '{}' {}-max Seat #{} is the button",
+                self.table, self.max_seats, self.button
+            ),
+        ];
+        for seat in &self.seats {
+            lines.push(format!("Seat {}: {} ({} in chips)", seat.seat, seat.name, seat.chips));
+        }
+        // Tracks everyone's cards, a draw's second line shows the cards kept
+        let mut state = ReplayState::start(self);
+        for event in &self.events {
+            lines.push(match event {
+                Event::Ante { player, amount } => format!("{}: posts the ante {}", player, amount),
+                Event::Blind { player, amount, big } => {
+                    format!("{}: posts {} blind {}", player, if *big { "big" } else { "small" }, amount)
+                }
+                Event::Bet { player, amount } => format!("{}: bets {}", player, amount),
+                Event::Call { player, amount } => format!("{}: calls {}", player, amount),
+                Event::Raise { player, amount, to } => format!("{}: raises {} to {}", player, amount, to),
+                Event::Check { player } => format!("{}: checks", player),
+                Event::Fold { player } => format!("{}: folds", player),
+                Event::Dealt { player, cards } => format!("Dealt to {} {}", player, cards_text(cards)),
+                Event::Draw { player, discarded, .. } if discarded.is_empty() => format!("{}: stands pat", player),
+                Event::Draw {
+                    player,
+                    discarded,
+                    drawn,
+                } => {
+                    let kept: Vec<Card> = state
+                        .players
+                        .iter()
+                        .find(|view| view.name == *player)
+                        .map(|view| view.cards.iter().filter(|card| !discarded.contains(card)).copied().collect())
+                        .unwrap_or_default();
+                    format!(
+                        "{}: discards {} card{} {}\nDealt to {} {} {}",
+                        player,
+                        discarded.len(),
+                        if discarded.len() == 1 { "" } else { "s" },
+                        cards_text(discarded),
+                        player,
+                        cards_text(&kept),
+                        cards_text(drawn)
+                    )
+                }
+                Event::Street { name, cards } if cards.is_empty() => format!("*** {} ***", name),
+                Event::Street { name, cards } if state.board.is_empty() => {
+                    format!("*** {} *** {}", name, cards_text(cards))
+                }
+                Event::Street { name, cards } => {
+                    format!("*** {} *** {} {}", name, cards_text(&state.board), cards_text(cards))
+                }
+                Event::Shows { player, cards } => match poker::best_of(cards) {
+                    Some(rank) => format!("{}: shows {} ({})", player, cards_text(cards), rank.category()),
+                    None => format!("{}: shows {}", player, cards_text(cards)),
+                },
+                Event::Returned { player, amount } => format!("Uncalled bet ({}) returned to {}", amount, player),
+                Event::Collected { player, amount } => format!("{} collected {} from pot", player, amount),
+            });
+            state.apply(event);
+        }
+
+        lines.push(String::from("*** SUMMARY ***"));
+        lines.push(format!("Total pot {} | Rake 0", self.pot()));
+        let board: Vec<Card> = self
+            .events
+            .iter()
+            .flat_map(|event| match event {
+                Event::Street { cards, .. } => cards.clone(),
+                _ => vec![],
+            })
+            .collect();
+        if !board.is_empty() {
+            lines.push(format!("Board {}", cards_text(&board)));
+        }
+        for seat in &self.seats {
+            let button = if seat.seat == self.button { " (button)" } else { "" };
+            let folded = self
+                .events
+                .iter()
+                .any(|event| matches!(event, Event::Fold { player } if *player == seat.name));
+            let won: i64 = self
+                .events
+                .iter()
+                .map(|event| match event {
+                    Event::Collected { player, amount } if *player == seat.name => *amount,
+                    _ => 0,
+                })
+                .sum();
+            let outcome = match (folded, won) {
+                (true, _) => String::from("folded"),
+                (false, 0) => String::from("lost"),
+                (false, won) => format!("won ({})", won),
+            };
+            lines.push(format!("Seat {}: {}{} {}", seat.seat, seat.name, button, outcome));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+impl fmt::Display for HandHistory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .ok_or_else(|| format!("expected cards in [ ], got '{}'", text.trim()))?;
+    inner
+        .split_whitespace()
+        .map(|code| code.parse::<Card>().map_err(|err| err.to_string()))
+        .collect()
+}
+
+fn parse_amount(text: &str) -> Result<i64, String> {
+    text.trim().parse().map_err(|_| format!("'{}' isn't an amount", text.trim()))
+}
+
+impl FromStr for HandHistory {
+    type Err = HistoryError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let fail = |line: usize, reason: String| HistoryError { line, reason };
+        let (first_line, header) = *lines.first().ok_or(fail(1, String::from("no hand")))?;
+
+        // PokerStars Hand #ID: GAME - TIME
+        let header_error = || fail(first_line, format!("'{}' isn't a PokerStars hand header", header));
+        let rest = header.strip_prefix("PokerStars Hand #").ok_or_else(header_error)?;
+        let (id, rest) = rest.split_once(": ").ok_or_else(header_error)?;
+        let (game, time) = rest.rsplit_once(" - ").ok_or_else(header_error)?;
+        let id = id.parse().map_err(|_| header_error())?;
+        let time = parse_time(time).map_err(|reason| fail(first_line, reason))?;
+
+        // Table 'NAME' N-max Seat #B is the button
+        let (table_line, table_text) = *lines.get(1).ok_or(fail(first_line, String::from("no table line")))?;
+        let table_error = || fail(table_line, format!("'{}' isn't a table line", table_text));
+        let rest = table_text.strip_prefix("Table '").ok_or_else(table_error)?;
+        let (table, rest) = rest.split_once("' ").ok_or_else(table_error)?;
+        let (max_seats, rest) = rest.split_once("-max Seat #").ok_or_else(table_error)?;
+        let button = rest.strip_suffix(" is the button").ok_or_else(table_error)?;
+        let max_seats = max_seats.parse().map_err(|_| table_error())?;
+        let button = button.parse().map_err(|_| table_error())?;
+
+        let mut history = HandHistory {
+            id,
+            game: game.to_string(),
+            time,
+            table: table.to_string(),
+            max_seats,
+            button,
+            seats: vec![],
+            events: vec![],
+        };
+
+        let mut index = 2;
+        // Seat N: NAME (X in chips)
+        while let Some((number, line)) = lines.get(index) {
+            let Some(rest) = line.strip_prefix("Seat ") else { break };
+            let seat_error = || fail(*number, format!("'{}' isn't a seat line", line));
+            let (seat, rest) = rest.split_once(": ").ok_or_else(seat_error)?;
+            let (name, chips) = rest.rsplit_once(" (").ok_or_else(seat_error)?;
+            let chips = chips.strip_suffix(" in chips)").ok_or_else(seat_error)?;
+            history.seats.push(SeatEntry {
+                seat: seat.parse().map_err(|_| seat_error())?,
+                name: name.to_string(),
+                chips: parse_amount(chips).map_err(|reason| fail(*number, reason))?,
+            });
+            index += 1;
+        }
+        if history.seats.is_empty() {
+            return Err(fail(table_line + 1, String::from("no seats")));
+        }
+
+        // Longest name first, so "Bob" doesn't match a line for "Bob Jr"
+        let mut names: Vec<String> = history.seats.iter().map(|seat| seat.name.clone()).collect();
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let player_line = |line: &str, separator: &str| -> Option<(String, String)> {
+            names.iter().find_map(|name| {
+                line.strip_prefix(name.as_str())
+                    .and_then(|rest| rest.strip_prefix(separator))
+                    .map(|rest| (name.clone(), rest.to_string()))
+            })
+        };
+
+        while let Some((number, line)) = lines.get(index) {
+            let number = *number;
+            index += 1;
+            if *line == "*** SUMMARY ***" {
+                break;
+            }
+            let event = if let Some(street) = line.strip_prefix("*** ") {
+                let (name, cards) = street
+                    .split_once(" ***")
+                    .ok_or_else(|| fail(number, format!("'{}' isn't a street", line)))?;
+                // With a board already out the line shows it first, only the last [ ] is new
+                let cards = cards.trim();
+                let (board, cards) = cards.split_at(cards.rfind('[').unwrap_or(0));
+                if !board.trim().is_empty() {
+                    let board = parse_cards(board).map_err(|reason| fail(number, reason))?;
+                    let so_far: Vec<Card> = history
+                        .events
+                        .iter()
+                        .flat_map(|event| match event {
+                            Event::Street { cards, .. } => cards.clone(),
+                            _ => vec![],
+                        })
+                        .collect();
+                    if board != so_far {
+                        return Err(fail(
+                            number,
+                            format!("the board so far is {}, not {}", cards_text(&so_far), cards_text(&board)),
+                        ));
+                    }
+                }
+                let cards = if cards.is_empty() {
+                    vec![]
+                } else {
+                    parse_cards(cards).map_err(|reason| fail(number, reason))?
+                };
+                Event::Street {
+                    name: name.to_string(),
+                    cards,
+                }
+            } else if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+                let (amount, player) = rest
+                    .split_once(") returned to ")
+                    .ok_or_else(|| fail(number, format!("can't read '{}'", line)))?;
+                if !names.iter().any(|name| name == player) {
+                    return Err(fail(number, format!("'{}' isn't seated", player)));
+                }
+                Event::Returned {
+                    player: player.to_string(),
+                    amount: parse_amount(amount).map_err(|reason| fail(number, reason))?,
+                }
+            } else if let Some((player, cards)) = player_line(line, " collected ") {
+                let amount = cards.strip_suffix(" from pot").unwrap_or(&cards);
+                Event::Collected {
+                    player,
+                    amount: parse_amount(amount).map_err(|reason| fail(number, reason))?,
+                }
+            } else if let Some(rest) = line.strip_prefix("Dealt to ") {
+                let (player, cards) = player_line(rest, " ")
+                    .ok_or_else(|| fail(number, format!("'{}' isn't seated", rest)))?;
+                Event::Dealt {
+                    player,
+                    cards: parse_cards(&cards).map_err(|reason| fail(number, reason))?,
+                }
+            } else if let Some((player, action)) = player_line(line, ": ") {
+                let amount = |prefix: &str| {
+                    parse_amount(action.strip_prefix(prefix).unwrap_or_default()).map_err(|reason| fail(number, reason))
+                };
+                match action.as_str() {
+                    "checks" => Event::Check { player },
+                    "folds" => Event::Fold { player },
+                    "stands pat" => Event::Draw {
+                        player,
+                        discarded: vec![],
+                        drawn: vec![],
+                    },
+                    _ if action.starts_with("posts the ante ") => Event::Ante {
+                        amount: amount("posts the ante ")?,
+                        player,
+                    },
+                    _ if action.starts_with("posts small blind ") => Event::Blind {
+                        amount: amount("posts small blind ")?,
+                        player,
+                        big: false,
+                    },
+                    _ if action.starts_with("posts big blind ") => Event::Blind {
+                        amount: amount("posts big blind ")?,
+                        player,
+                        big: true,
+                    },
+                    _ if action.starts_with("bets ") => Event::Bet {
+                        amount: amount("bets ")?,
+                        player,
+                    },
+                    _ if action.starts_with("calls ") => Event::Call {
+                        amount: amount("calls ")?,
+                        player,
+                    },
+                    _ if action.starts_with("raises ") => {
+                        let (by, to) = action["raises ".len()..]
+                            .split_once(" to ")
+                            .ok_or_else(|| fail(number, format!("'{}' should be raises X to Y", action)))?;
+                        Event::Raise {
+                            amount: parse_amount(by).map_err(|reason| fail(number, reason))?,
+                            to: parse_amount(to).map_err(|reason| fail(number, reason))?,
+                            player,
+                        }
+                    }
+                    _ if action.starts_with("shows ") => {
+                        // The hand's name in ( ) after the cards is only for people
+                        let cards = action["shows ".len()..].split(" (").next().unwrap_or_default();
+                        Event::Shows {
+                            player,
+                            cards: parse_cards(cards).map_err(|reason| fail(number, reason))?,
+                        }
+                    }
+                    _ if action.starts_with("discards ") => {
+                        let discarded = action
+                            .find('[')
+                            .map(|start| parse_cards(&action[start..]))
+                            .transpose()
+                            .map_err(|reason| fail(number, reason))?
+                            .unwrap_or_default();
+                        // The new cards are the second [ ] on the "Dealt to" line after it
+                        let (next, dealt) = lines
+                            .get(index)
+                            .ok_or(fail(number, String::from("a discard needs a 'Dealt to' line after it")))?;
+                        let drawn = dealt
+                            .rfind('[')
+                            .filter(|_| dealt.starts_with("Dealt to "))
+                            .map(|start| parse_cards(&dealt[start..]))
+                            .ok_or_else(|| fail(*next, format!("'{}' should deal the drawn cards", dealt)))?
+                            .map_err(|reason| fail(*next, reason))?;
+                        index += 1;
+                        Event::Draw {
+                            player,
+                            discarded,
+                            drawn,
+                        }
+                    }
+                    _ => return Err(fail(number, format!("'{}' isn't an action", action))),
+                }
+            } else {
+                return Err(fail(number, format!("can't read '{}'", line)));
+            };
+            history.events.push(event);
+        }
+        Ok(history)
+    }
+}
+
+/// Every hand in a file. Hands start at each "PokerStars Hand #" line
+pub fn parse_all(text: &str) -> Result<Vec<HandHistory>, HistoryError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let starts: Vec<usize> = (0..lines.len())
+        .filter(|index| lines[*index].trim_start().starts_with("PokerStars Hand #"))
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(hand, start)| {
+            let end = starts.get(hand + 1).copied().unwrap_or(lines.len());
+            lines[*start..end].join("\n").parse::<HandHistory>().map_err(|err| HistoryError {
+                line: err.line + start,
+                reason: err.reason,
+            })
+        })
+        .collect()
+}
+
+pub fn write_all(hands: &[HandHistory]) -> String {
+    let hands: Vec<String> = hands.iter().map(HandHistory::to_text).collect();
+    hands.join("\n")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerView {
+    pub name: String,
+    pub chips: i64,
+    pub cards: Vec<Card>,
+    pub folded: bool,
+    pub street_bet: i64, // Put in on this street, blinds included and antes not
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayState {
+    pub players: Vec<PlayerView>,
+    pub board: Vec<Card>,
+    pub pot: i64,
+    pub street: Option<String>,
+}
+
+impl ReplayState {
+    fn start(history: &HandHistory) -> Self {
+        ReplayState {
+            players: history
+                .seats
+                .iter()
+                .map(|seat| PlayerView {
+                    name: seat.name.clone(),
+                    chips: seat.chips,
+                    cards: vec![],
+                    folded: false,
+                    street_bet: 0,
+                })
+                .collect(),
+            board: vec![],
+            pot: 0,
+            street: None,
+        }
+    }
+
+    fn player_mut(&mut self, name: &str) -> Option<&mut PlayerView> {
+        self.players.iter_mut().find(|player| player.name == name)
+    }
+
+    // Chips the event moves into the pot, less than nothing for chips going back out
+    fn chips_in(&self, event: &Event) -> i64 {
+        match event {
+            Event::Ante { amount, .. }
+            | Event::Blind { amount, .. }
+            | Event::Bet { amount, .. }
+            | Event::Call { amount, .. } => *amount,
+            Event::Raise { player: name, to, .. } => {
+                to - self.players.iter().find(|player| player.name == *name).map_or(0, |player| player.street_bet)
+            }
+            Event::Returned { amount, .. } | Event::Collected { amount, .. } => -amount,
+            _ => 0,
+        }
+    }
+
+    fn apply(&mut self, event: &Event) {
+        let chips = self.chips_in(event);
+        match event {
+            Event::Ante { player: name, .. } => {
+                if let Some(player) = self.player_mut(name) {
+                    player.chips -= chips;
+                }
+                self.pot += chips;
+            }
+            Event::Blind { player: name, .. }
+            | Event::Bet { player: name, .. }
+            | Event::Call { player: name, .. }
+            | Event::Raise { player: name, .. } => {
+                if let Some(player) = self.player_mut(name) {
+                    player.chips -= chips;
+                    player.street_bet += chips;
+                }
+                self.pot += chips;
+            }
+            Event::Check { .. } => {}
+            Event::Fold { player: name } => {
+                if let Some(player) = self.player_mut(name) {
+                    player.folded = true;
+                }
+            }
+            Event::Dealt { player: name, cards } | Event::Shows { player: name, cards } => {
+                if let Some(player) = self.player_mut(name) {
+                    player.cards = cards.clone();
+                }
+            }
+            Event::Draw {
+                player: name,
+                discarded,
+                drawn,
+            } => {
+                if let Some(player) = self.player_mut(name) {
+                    player.cards.retain(|card| !discarded.contains(card));
+                    player.cards.extend(drawn);
+                }
+            }
+            Event::Street { name, cards } => {
+                self.street = Some(name.clone());
+                self.board.extend(cards);
+                for player in &mut self.players {
+                    player.street_bet = 0;
+                }
+            }
+            Event::Returned { player: name, .. } | Event::Collected { player: name, .. } => {
+                if let Some(player) = self.player_mut(name) {
+                    player.chips -= chips;
+                }
+                self.pot += chips;
+            }
+        }
+    }
+}
+
+impl fmt::Display for ReplayState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(street) = &self.street {
+            write!(f, "{} ", street)?;
+        }
+        if !self.board.is_empty() {
+            write!(f, "{} ", cards_text(&self.board))?;
+        }
+        writeln!(f, "pot {}", self.pot)?;
+        for player in &self.players {
+            let folded = if player.folded { " (folded)" } else { "" };
+            writeln!(f, "  {:<14} {:>5} {}{}", player.name, player.chips, cards_text(&player.cards), folded)?;
+        }
+        Ok(())
+    }
+}
+
+/// Steps through a hand's events, forwards and back
+pub struct Replayer<'a> {
+    history: &'a HandHistory,
+    position: usize, // Events applied so far
+    state: ReplayState,
+}
+
+impl<'a> Replayer<'a> {
+    pub fn new(history: &'a HandHistory) -> Self {
+        Replayer {
+            history,
+            position: 0,
+            state: ReplayState::start(history),
+        }
+    }
+
+    /// Applies the next event and returns it, None at the end of the hand
+    pub fn forward(&mut self) -> Option<&'a Event> {
+        let event = self.history.events.get(self.position)?;
+        self.state.apply(event);
+        self.position += 1;
+        Some(event)
+    }
+
+    /// Undoes the last event. False at the start of the hand
+    pub fn back(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        // Events can't be undone on their own, so play from the start to one short of here
+        let target = self.position - 1;
+        self.state = ReplayState::start(self.history);
+        self.position = 0;
+        while self.position < target {
+            self.forward();
+        }
+        true
+    }
+
+    pub fn state(&self) -> &ReplayState {
+        &self.state
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.events.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.len()
+    }
+}
+
+/// Describes an event the way the history writes it, without the second line of a draw
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Ante { player, amount } => write!(f, "{} posts the ante {}", player, amount),
+            Event::Blind { player, amount, big } => {
+                write!(f, "{} posts the {} blind {}", player, if *big { "big" } else { "small" }, amount)
+            }
+            Event::Bet { player, amount } => write!(f, "{} bets {}", player, amount),
+            Event::Call { player, amount } => write!(f, "{} calls {}", player, amount),
+            Event::Raise { player, amount, to } => write!(f, "{} raises {} to {}", player, amount, to),
+            Event::Check { player } => write!(f, "{} checks", player),
+            Event::Fold { player } => write!(f, "{} folds", player),
+            Event::Dealt { player, cards } => write!(f, "{} is dealt {}", player, cards_text(cards)),
+            Event::Draw { player, discarded, .. } if discarded.is_empty() => write!(f, "{} stands pat", player),
+            Event::Draw {
+                player,
+                discarded,
+                drawn,
+            } => write!(f, "{} discards {} and draws {}", player, cards_text(discarded), cards_text(drawn)),
+            Event::Street { name, cards } if cards.is_empty() => write!(f, "*** {} ***", name),
+            Event::Street { name, cards } => write!(f, "*** {} *** {}", name, cards_text(cards)),
+            Event::Shows { player, cards } => write!(f, "{} shows {}", player, cards_text(cards)),
+            Event::Returned { player, amount } => write!(f, "{} gets {} back, nobody called it", player, amount),
+            Event::Collected { player, amount } => write!(f, "{} collects {}", player, amount),
+        }
+    }
+}
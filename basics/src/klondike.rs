@@ -0,0 +1,592 @@
+use super::card::{Card, Rank, Suit};
+use super::deck::Deck;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/**
+Klondike solitaire
+
+- Seven tableau columns dealt 1 to 7 cards, the top card of each face up, the other 24 in the stock
+- Columns build down in alternating colours, only a King goes in an empty column
+- Foundations build up by suit from the Ace. Aces are low here (Rank says Ace is high)
+- Draw one or three from the stock. An empty stock turns the waste back over (a redeal)
+- Face down cards turn over by themselves once uncovered
+- Scoring is the Windows standard: waste to column 5, to a foundation 10, turning a card 5,
+  foundation back to a column -15, a redeal -100 drawing one or -20 drawing three, never below 0
+
+Board is the cards and the rules. Klondike adds the score and undo/redo, solve() searches Boards.
+A deal needs exactly one standard deck and a draw of at least one card
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub draw: usize,          // 1 or 3, never 0
+    pub redeals: Option<u32>, // None for unlimited
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules { draw: 1, redeals: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Draw, // Turns the waste back over when the stock is empty
+    WasteToFoundation,
+    WasteToTableau(usize),
+    TableauToFoundation(usize),
+    TableauToTableau { from: usize, count: usize, to: usize },
+    FoundationToTableau { suit: Suit, to: usize },
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Move::Draw => write!(f, "draw"),
+            Move::WasteToFoundation => write!(f, "waste to foundation"),
+            Move::WasteToTableau(to) => write!(f, "waste to column {}", to),
+            Move::TableauToFoundation(from) => write!(f, "column {} to foundation", from),
+            Move::TableauToTableau { from, count: 1, to } => write!(f, "column {} to column {}", from, to),
+            Move::TableauToTableau { from, count, to } => {
+                write!(f, "{} cards from column {} to column {}", count, from, to)
+            }
+            Move::FoundationToTableau { suit, to } => write!(f, "{} foundation to column {}", suit, to),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KlondikeError {
+    IllegalMove(Move, String),
+    BadDeck(String),
+    BadRules(String),
+    NothingToUndo,
+    NothingToRedo,
+}
+
+impl fmt::Display for KlondikeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KlondikeError::IllegalMove(attempted, reason) => write!(f, "can't move {}: {}", attempted, reason),
+            KlondikeError::BadDeck(reason) => write!(f, "can't deal from this deck: {}", reason),
+            KlondikeError::BadRules(reason) => write!(f, "can't play these rules: {}", reason),
+            KlondikeError::NothingToUndo => write!(f, "nothing to undo"),
+            KlondikeError::NothingToRedo => write!(f, "nothing to redo"),
+        }
+    }
+}
+
+impl std::error::Error for KlondikeError {}
+
+/// Ace is 1, King 13
+pub fn rank_number(card: &Card) -> u8 {
+    match card.rank {
+        Rank::Ace => 1,
+        rank => rank.value(),
+    }
+}
+
+fn is_red(card: &Card) -> bool {
+    matches!(card.suit, Suit::Diamonds | Suit::Hearts)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Column {
+    pub cards: Vec<Card>, // Top card last
+    pub face_down: usize, // The first this many cards are face down
+}
+
+impl Column {
+    pub fn face_up(&self) -> &[Card] {
+        &self.cards[self.face_down..]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    pub stock: Vec<Card>, // Top card last
+    pub waste: Vec<Card>, // Top card last
+    pub foundations: [Vec<Card>; 4], // In Suit::ALL order
+    pub tableau: [Column; 7],
+    pub redeals: u32,
+    pub rules: Rules,
+}
+
+impl Board {
+    /// Deals from the top of the deck, row by row like at a table
+    pub fn deal(mut deck: Deck, rules: Rules) -> Result<Self, KlondikeError> {
+        if rules.draw == 0 {
+            return Err(KlondikeError::BadRules(String::from("draw at least one card")));
+        }
+        if deck.remaining() != 52 {
+            return Err(KlondikeError::BadDeck(format!("{} cards, not 52", deck.remaining())));
+        }
+        let mut seen = HashSet::new();
+        if let Some(card) = deck.cards().iter().find(|card| !seen.insert(**card)) {
+            return Err(KlondikeError::BadDeck(format!("{} more than once", card)));
+        }
+
+        let mut tableau: [Column; 7] = Default::default();
+        for row in 0..7 {
+            for (index, column) in tableau.iter_mut().enumerate().skip(row) {
+                column.cards.push(deck.draw_one().unwrap());
+                column.face_down = index;
+            }
+        }
+        Ok(Board {
+            stock: deck.into_cards(),
+            waste: vec![],
+            foundations: Default::default(),
+            tableau,
+            redeals: 0,
+            rules,
+        })
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.foundations.iter().all(|pile| pile.len() == 13)
+    }
+
+    fn foundation(&self, suit: Suit) -> &Vec<Card> {
+        &self.foundations[suit as usize]
+    }
+
+    fn fits_foundation(&self, card: &Card) -> bool {
+        self.foundation(card.suit).len() as u8 + 1 == rank_number(card)
+    }
+
+    fn fits_column(&self, card: &Card, to: usize) -> bool {
+        match self.tableau[to].cards.last() {
+            None => card.rank == Rank::King,
+            Some(top) => rank_number(top) == rank_number(card) + 1 && is_red(top) != is_red(card),
+        }
+    }
+
+    // Moving this many cards turns a face down card over
+    fn reveals(&self, from: usize, count: usize) -> bool {
+        let column = &self.tableau[from];
+        count == column.cards.len() - column.face_down && column.face_down > 0
+    }
+
+    fn check(&self, attempted: Move) -> Result<(), String> {
+        match attempted {
+            Move::Draw => {
+                let out_of_redeals = self.rules.redeals.is_some_and(|allowed| self.redeals >= allowed);
+                if self.stock.is_empty() && (self.waste.is_empty() || out_of_redeals) {
+                    return Err(String::from("the stock is empty"));
+                }
+            }
+            Move::WasteToFoundation => {
+                let card = self.waste.last().ok_or("the waste is empty")?;
+                if !self.fits_foundation(card) {
+                    return Err(format!("{} doesn't go on its foundation yet", card));
+                }
+            }
+            Move::WasteToTableau(to) => {
+                let card = self.waste.last().ok_or("the waste is empty")?;
+                if to >= 7 || !self.fits_column(card, to) {
+                    return Err(format!("{} doesn't go on column {}", card, to));
+                }
+            }
+            Move::TableauToFoundation(from) => {
+                let card = self.tableau.get(from).and_then(|column| column.cards.last());
+                let card = card.ok_or(format!("column {} is empty", from))?;
+                if !self.fits_foundation(card) {
+                    return Err(format!("{} doesn't go on its foundation yet", card));
+                }
+            }
+            Move::TableauToTableau { from, count, to } => {
+                if from >= 7 || to >= 7 || from == to {
+                    return Err(String::from("pick two different columns 0 to 6"));
+                }
+                let face_up = self.tableau[from].face_up();
+                if count == 0 || count > face_up.len() {
+                    return Err(format!("column {} has {} face up cards", from, face_up.len()));
+                }
+                let card = &face_up[face_up.len() - count];
+                if !self.fits_column(card, to) {
+                    return Err(format!("{} doesn't go on column {}", card, to));
+                }
+            }
+            Move::FoundationToTableau { suit, to } => {
+                let card = self.foundation(suit).last().ok_or(format!("the {} foundation is empty", suit))?;
+                if to >= 7 || !self.fits_column(card, to) {
+                    return Err(format!("{} doesn't go on column {}", card, to));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the move and returns the points it scores
+    pub fn apply(&mut self, attempted: Move) -> Result<i32, KlondikeError> {
+        self.check(attempted)
+            .map_err(|reason| KlondikeError::IllegalMove(attempted, reason))?;
+
+        let mut points = match attempted {
+            Move::Draw if self.stock.is_empty() => {
+                self.stock = self.waste.drain(..).rev().collect();
+                self.redeals += 1;
+                if self.rules.draw == 1 {
+                    -100
+                } else {
+                    -20
+                }
+            }
+            Move::Draw => {
+                let count = self.rules.draw.min(self.stock.len());
+                for _ in 0..count {
+                    let card = self.stock.pop().unwrap();
+                    self.waste.push(card);
+                }
+                0
+            }
+            Move::WasteToFoundation => {
+                let card = self.waste.pop().unwrap();
+                self.foundations[card.suit as usize].push(card);
+                10
+            }
+            Move::WasteToTableau(to) => {
+                let card = self.waste.pop().unwrap();
+                self.tableau[to].cards.push(card);
+                5
+            }
+            Move::TableauToFoundation(from) => {
+                let card = self.tableau[from].cards.pop().unwrap();
+                self.foundations[card.suit as usize].push(card);
+                10
+            }
+            Move::TableauToTableau { from, count, to } => {
+                let column = &mut self.tableau[from].cards;
+                let moved = column.split_off(column.len() - count);
+                self.tableau[to].cards.extend(moved);
+                0
+            }
+            Move::FoundationToTableau { suit, to } => {
+                let card = self.foundations[suit as usize].pop().unwrap();
+                self.tableau[to].cards.push(card);
+                -15
+            }
+        };
+
+        // Turn over anything uncovered
+        for column in self.tableau.iter_mut() {
+            if column.face_down > 0 && column.face_down == column.cards.len() {
+                column.face_down -= 1;
+                points += 5;
+            }
+        }
+        Ok(points)
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = vec![Move::Draw, Move::WasteToFoundation];
+        for column in 0..7 {
+            moves.push(Move::WasteToTableau(column));
+            moves.push(Move::TableauToFoundation(column));
+            for suit in Suit::ALL {
+                moves.push(Move::FoundationToTableau { suit, to: column });
+            }
+            for to in 0..7 {
+                for count in 1..=self.tableau[column].face_up().len() {
+                    moves.push(Move::TableauToTableau { from: column, count, to });
+                }
+            }
+        }
+        moves.retain(|candidate| self.check(*candidate).is_ok());
+        moves
+    }
+
+    /// A card that can go up without ever being needed in the tableau: nothing that could
+    /// be built on it is still out
+    fn safe_to_foundation(&self, card: &Card) -> bool {
+        let rank = rank_number(card);
+        rank <= 2
+            || Suit::ALL.iter().all(|suit| {
+                let needed = if is_red(&Card::new(Rank::Two, *suit)) == is_red(card) {
+                    rank.saturating_sub(2)
+                } else {
+                    rank - 1
+                };
+                self.foundation(*suit).len() as u8 >= needed
+            })
+    }
+
+    // Candidate moves for the solver, best first, as (draws first, move). Drawing is never a move on
+    // its own: every card the stock can bring to the top of the waste is tried straight away, which
+    // cuts out the many positions that only differ in how far through the stock you are.
+    // Cards never come back off the foundations, a King already heading a column stays put, and a
+    // run is only split to get the card under it onto a foundation
+    fn solver_moves(&self) -> Vec<(usize, Move)> {
+        let mut to_foundation = vec![];
+        if let Some(card) = self.waste.last() {
+            if self.fits_foundation(card) {
+                if self.safe_to_foundation(card) {
+                    return vec![(0, Move::WasteToFoundation)];
+                }
+                to_foundation.push((0, Move::WasteToFoundation));
+            }
+        }
+        for (from, column) in self.tableau.iter().enumerate() {
+            if let Some(card) = column.cards.last() {
+                if self.fits_foundation(card) {
+                    if self.safe_to_foundation(card) {
+                        return vec![(0, Move::TableauToFoundation(from))];
+                    }
+                    to_foundation.push((0, Move::TableauToFoundation(from)));
+                }
+            }
+        }
+
+        let mut revealing = vec![];
+        let mut other = vec![];
+        for from in 0..7 {
+            let face_up = self.tableau[from].face_up().len();
+            for count in 1..=face_up {
+                let whole_column = count == self.tableau[from].cards.len();
+                let card = &self.tableau[from].cards[self.tableau[from].cards.len() - count];
+                for to in 0..7 {
+                    // Checked here rather than with check(), which builds an error message for every miss
+                    if to == from || !self.fits_column(card, to) || (whole_column && self.tableau[to].cards.is_empty()) {
+                        continue;
+                    }
+                    let candidate = (0, Move::TableauToTableau { from, count, to });
+                    if self.reveals(from, count) || whole_column {
+                        revealing.push(candidate);
+                    } else if count < face_up {
+                        // Splitting a run only helps if the card it uncovers can go up
+                        let uncovered = &self.tableau[from].cards[self.tableau[from].cards.len() - count - 1];
+                        if self.fits_foundation(uncovered) {
+                            other.push(candidate);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Draw through the stock once, turning the waste over at most once, playing whatever comes up
+        let mut from_stock = vec![];
+        let mut board = self.clone();
+        let mut draws = 0;
+        let mut turned_over = false;
+        loop {
+            if let Some(card) = board.waste.last() {
+                if draws > 0 && board.fits_foundation(card) {
+                    from_stock.push((draws, Move::WasteToFoundation));
+                }
+                from_stock.extend((0..7).filter(|to| board.fits_column(card, *to)).map(|to| (draws, Move::WasteToTableau(to))));
+            }
+            if board.stock.is_empty() {
+                if turned_over {
+                    break;
+                }
+                turned_over = true;
+            }
+            if board.apply(Move::Draw).is_err() {
+                break;
+            }
+            draws += 1;
+        }
+
+        let mut moves = to_foundation;
+        moves.extend(revealing);
+        moves.extend(from_stock);
+        moves.extend(other);
+        moves
+    }
+
+    // Columns can be swapped without changing anything, so they're sorted before hashing
+    fn key(&self) -> u64 {
+        let mut columns: Vec<&Column> = self.tableau.iter().collect();
+        columns.sort_by_key(|column| (column.face_down, column.cards.iter().map(Card::index).collect::<Vec<_>>()));
+
+        let mut hasher = DefaultHasher::new();
+        self.stock.hash(&mut hasher);
+        self.waste.hash(&mut hasher);
+        for pile in &self.foundations {
+            pile.len().hash(&mut hasher);
+        }
+        columns.hash(&mut hasher);
+        if self.rules.redeals.is_some() {
+            self.redeals.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let top = |pile: &Vec<Card>| pile.last().map_or(String::from("--"), |card| card.code());
+        let foundations: Vec<String> = self.foundations.iter().map(top).collect();
+        writeln!(
+            f,
+            "Stock {:>2}  Waste {}  Foundations {}",
+            self.stock.len(),
+            top(&self.waste),
+            foundations.join(" ")
+        )?;
+        for (index, column) in self.tableau.iter().enumerate() {
+            let cards: Vec<String> = column
+                .cards
+                .iter()
+                .enumerate()
+                .map(|(position, card)| {
+                    if position < column.face_down {
+                        String::from("##")
+                    } else {
+                        card.code()
+                    }
+                })
+                .collect();
+            writeln!(f, "{}: {}", index, cards.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A game being played: the board, the score and the moves to undo and redo
+#[derive(Debug, Clone)]
+pub struct Klondike {
+    board: Board,
+    score: i32,
+    undo: Vec<(Board, i32)>,
+    redo: Vec<(Board, i32)>,
+}
+
+impl Klondike {
+    pub fn new(deck: Deck, rules: Rules) -> Result<Self, KlondikeError> {
+        Ok(Klondike {
+            board: Board::deal(deck, rules)?,
+            score: 0,
+            undo: vec![],
+            redo: vec![],
+        })
+    }
+
+    /// The same seed always deals the same game
+    pub fn seeded(seed: u64, rules: Rules) -> Result<Self, KlondikeError> {
+        let mut deck = Deck::with_seed(seed);
+        deck.shuffle();
+        Klondike::new(deck, rules)
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.board.is_won()
+    }
+
+    pub fn play(&mut self, attempted: Move) -> Result<(), KlondikeError> {
+        let before = (self.board.clone(), self.score);
+        let points = self.board.apply(attempted)?;
+        self.score = (self.score + points).max(0);
+        self.undo.push(before);
+        self.redo.clear();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Result<(), KlondikeError> {
+        let (board, score) = self.undo.pop().ok_or(KlondikeError::NothingToUndo)?;
+        let after = std::mem::replace(&mut self.board, board);
+        self.redo.push((after, self.score));
+        self.score = score;
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), KlondikeError> {
+        let (board, score) = self.redo.pop().ok_or(KlondikeError::NothingToRedo)?;
+        let before = std::mem::replace(&mut self.board, board);
+        self.undo.push((before, self.score));
+        self.score = score;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Solution {
+    Solved(Vec<Move>),
+    NoSolutionFound, // Searched everything it tries, see solve()
+    GaveUp, // Hit the node limit first
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveReport {
+    pub solution: Solution,
+    pub nodes: u64, // Distinct positions looked at
+}
+
+struct Frame {
+    board: Board,
+    moves: Vec<(usize, Move)>,
+    next: usize,
+}
+
+/// Depth first search, skipping any position already seen. Stops after `node_limit` positions.
+/// The search skips moves that almost never matter (see solver_moves), so NoSolutionFound means none
+/// of the moves it tries win, not that the deal is unwinnable. One of the others might still win it
+pub fn solve(board: &Board, node_limit: u64) -> SolveReport {
+    let mut seen = HashSet::new();
+    seen.insert(board.key());
+    let mut stack = vec![Frame {
+        board: board.clone(),
+        moves: board.solver_moves(),
+        next: 0,
+    }];
+    let mut nodes = 1;
+
+    while let Some(frame) = stack.last_mut() {
+        if frame.board.is_won() {
+            // Each frame below the top took the move before its `next`
+            let mut path = vec![];
+            for frame in &stack[..stack.len() - 1] {
+                let (draws, chosen) = frame.moves[frame.next - 1];
+                path.extend(std::iter::repeat_n(Move::Draw, draws));
+                path.push(chosen);
+            }
+            return SolveReport {
+                solution: Solution::Solved(path),
+                nodes,
+            };
+        }
+        if frame.next == frame.moves.len() {
+            stack.pop();
+            continue;
+        }
+
+        let (draws, attempted) = frame.moves[frame.next];
+        frame.next += 1;
+        let mut board = frame.board.clone();
+        for _ in 0..draws {
+            board.apply(Move::Draw).expect("solver only tries legal moves");
+        }
+        board.apply(attempted).expect("solver only tries legal moves");
+        if !seen.insert(board.key()) {
+            continue;
+        }
+        nodes += 1;
+        if nodes > node_limit {
+            return SolveReport {
+                solution: Solution::GaveUp,
+                nodes,
+            };
+        }
+        stack.push(Frame {
+            moves: board.solver_moves(),
+            board,
+            next: 0,
+        });
+    }
+
+    SolveReport {
+        solution: Solution::NoSolutionFound,
+        nodes,
+    }
+}
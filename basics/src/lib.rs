@@ -0,0 +1,22 @@
+pub mod blackjack;
+pub mod bridge;
+pub mod card;
+pub mod cli;
+pub mod commitment;
+pub mod deck;
+pub mod deck_spec;
+pub mod draw_poker;
+pub mod hand_history;
+pub mod klondike;
+pub mod odds;
+pub mod persistence;
+pub mod poker;
+pub mod render;
+pub mod replay;
+pub mod server;
+pub mod shoe;
+pub mod shuffle_quality;
+pub mod shuffles;
+pub mod simulation;
+pub mod table;
+pub mod tournament;
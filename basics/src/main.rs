@@ -1,60 +1,327 @@
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use basics::bridge::{self, BridgeDeal};
+use basics::card::{self, Card};
+use basics::commitment::{self, Reveal};
+use basics::deck::Deck;
+use basics::deck_spec::DeckSpec;
+use basics::draw_poker::{self, DrawAction, DrawPoker, KeepGroups, StandPat};
+use basics::hand_history::{self, HandHistory, Replayer};
+use basics::persistence::SavedGame;
+use basics::poker;
+use basics::render::Style;
+use basics::replay::{Action, GameLog, RecordedGame};
+use basics::shoe::Shoe;
+use basics::table::Table;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::Path;
 
-#[derive(Debug)]
-struct Deck {
-    cards: Vec<String>, //Vectors---- like arrays but can change size
+fn describe(cards: &[Card]) -> Vec<String> {
+    cards.iter().map(|card| card.to_string()).collect()
 }
 
-impl Deck {
-    fn new() -> Self {
-        /** Inherit implementaiton a.k.a. Add a function to a struct....can define methods and functions
-        - Associated functions ---- not tied to an instance
-        - Methods -----tied to an instance
-
-        */
-        //Arrays vs Vectors performance difference is tiny and is a matter of communication
-        let suits = ["Hearts", "Spades", "Diamonds"];
-        let suits2 = vec!["Hearts", "Spades", "Diamonds"];
-        let values = ["Ace", "Two", "Three"];
-
-        /*
-        - Variables are immutable by default. Technical an immutable variable is called a Binding
-         */
-        let mut cards = vec![];
-
-        for suit in suits {
-            for value in values {
-                let card = format!("{} of {}", value, suit);
-                cards.push(card);
-            }
-        }
-        // println!("Heres your deck: {:?}", cards);
-        // return Deck { cards };
-        // return deck;
-        // deck
-        Deck { cards } //Implicit return - auto return the last expression
+fn main() {
+    let mut deck = Deck::new();
+    println!("Heres your deck: {:?}", describe(deck.cards()));
+    deck.shuffle();
+    let mut cards = deck.deal(3).expect("a new deck has 52 cards");
+    cards.sort(); // By suit, then rank
+
+    // let deck2 = Deck { cards: vec![] };
+    // let deck2 = Deck { cards: Vec::new() };
+
+    println!("Heres your deck shuffled: {:?}", describe(deck.cards()));
+    println!("Heres your cards: {:?}", describe(&cards));
+
+    // Cards round trip through both text forms
+    for card in &cards {
+        let long: Card = card.to_string().parse().unwrap();
+        let short: Card = card.code().parse().unwrap();
+        println!("{} = {} = {}", card.code(), long, short);
+    }
+    match "Eleven of Cups".parse::<Card>() {
+        Ok(card) => println!("{}", card),
+        Err(reason_this_failed) => println!("{}", reason_this_failed),
     }
 
-    fn shuffle(&mut self) {
-        let mut rng = thread_rng();
-        self.cards.shuffle(&mut rng);
+    // Asking for too many cards is an error, not a crash
+    match deck.deal(100) {
+        Ok(hand) => println!("Dealt {} cards", hand.len()),
+        Err(reason_this_failed) => println!("{}", reason_this_failed),
     }
+    if let Some(top) = deck.peek() {
+        println!("Top card is {}", top);
+    }
+    let last_cards = deck.deal_up_to(100);
+    println!("Dealt the last {} cards, {} remaining", last_cards.len(), deck.remaining());
+    println!("Drawing from an empty deck: {:?}", deck.draw_one());
+
+    deck.discard(cards);
+    deck.discard(last_cards);
+    println!("{} cards in the discard pile", deck.discards().len());
+    deck.reshuffle_discards();
+    println!("Reshuffled, {} cards in the deck", deck.remaining());
 
-    fn deal(&mut self, num_cards: usize) -> Vec<String> { //TODO: See diagram for list of number types in Rust
-        self.cards.split_off(self.cards.len() - num_cards)
+    // Same seed, same order. Bug reports can quote the seed
+    let mut first = Deck::new();
+    first.shuffle_seeded(42);
+    let mut second = Deck::new();
+    second.shuffle_seeded(42);
+    println!(
+        "Seed {:?} twice gives the same deck: {}",
+        first.seed(),
+        first.cards() == second.cards()
+    );
+    let mut own_rng = StdRng::seed_from_u64(7);
+    second.shuffle_with(&mut own_rng);
+
+    // Record a game, save it as text, load it back and replay it
+    let mut game = RecordedGame::new(2024);
+    game.play(Action::Shuffle).unwrap();
+    let hand = game.play(Action::Deal(5)).unwrap();
+    game.play(Action::Draw).unwrap();
+    game.play(Action::Discard(hand.clone())).unwrap();
+    game.play(Action::ReshuffleDiscards).unwrap();
+    if let Err(reason_this_failed) = game.play(Action::Deal(60)) {
+        println!("Not recorded: {}", reason_this_failed); // Failed actions don't go in the log
+    }
+    if let Err(reason_this_failed) = game.play(Action::Discard(hand.clone())) {
+        println!("Not recorded: {}", reason_this_failed); // Already discarded
     }
-}
 
-fn main() {
-    let mut deck = Deck::new();
-    println!("Heres your deck: {:?}", deck);
-    deck.shuffle();
-    let cards = deck.deal(3);// Consider error handling.
+    let saved = game.log().to_string();
+    print!("{}", saved);
+    let loaded: GameLog = saved.parse().expect("log we just wrote should parse");
+    let (replayed, dealt) = loaded.replay().expect("recorded actions all succeeded");
+    println!(
+        "Replay deals the same hand: {}, ends with the same deck: {}",
+        dealt[1] == hand,
+        replayed.cards() == game.deck().cards()
+    );
 
-    let deck2 = Deck { cards: vec![] };
-    let deck2 = Deck { cards: Vec::new() };
+    // Six deck blackjack shoe, cut card three quarters of the way in
+    let mut shoe = Shoe::with_seed(6, 0.75, 99).expect("6 decks at 75% is a valid shoe");
+    shoe.burn(1);
+    let mut rounds = 0;
+    while !shoe.cut_card_reached() {
+        shoe.start_round();
+        let round: Vec<Card> = (0..10).filter_map(|_| shoe.draw()).collect();
+        shoe.discard(round);
+        rounds += 1;
+    }
+    println!(
+        "{} decks: cut card out after {} rounds, {} cards left, {} before the cut",
+        shoe.decks(),
+        rounds,
+        shoe.remaining(),
+        shoe.cards_until_cut()
+    );
+    println!("Aces left: {}", shoe.remaining_by_rank()[&card::Rank::Ace]);
+    println!(
+        "Hi-Lo running count {}, true count {:.2}, next card an Ace: {:.3}",
+        shoe.running_count(),
+        shoe.true_count(),
+        shoe.odds().probability_rank(card::Rank::Ace)
+    );
+    println!("Reshuffled: {}, {} cards in the shoe", shoe.start_round(), shoe.remaining());
+    if let Err(reason_this_failed) = Shoe::new(8, 1.5) {
+        println!("{}", reason_this_failed);
+    }
 
-    println!("Heres your deck shuffled: {:?}", deck);
-    println!("Heres your cards: {:?}", cards);
-}
\ No newline at end of file
+    // Two players, one board
+    let mut deck = Deck::with_seed(5);
+    deck.shuffle();
+    let alice: [Card; 2] = deck.deal(2).unwrap().try_into().unwrap();
+    let bob: [Card; 2] = deck.deal(2).unwrap().try_into().unwrap();
+    let board: [Card; 5] = deck.deal(5).unwrap().try_into().unwrap();
+    let (alice_best, bob_best) = (poker::holdem(&alice, &board), poker::holdem(&bob, &board));
+    println!("Board: {:?}", board.map(|card| card.code()));
+    println!("Alice {:?}: {}", alice.map(|card| card.code()), alice_best);
+    println!("Bob {:?}: {}", bob.map(|card| card.code()), bob_best);
+    println!("{}", match alice_best.cmp(&bob_best) {
+        std::cmp::Ordering::Greater => "Alice wins",
+        std::cmp::Ordering::Less => "Bob wins",
+        std::cmp::Ordering::Equal => "Split pot",
+    });
+    // Other games' decks come from config files and use the same shuffle and deal
+    let decks = Path::new(env!("CARGO_MANIFEST_DIR")).join("decks");
+    for file in ["french_jokers.deck", "pinochle.deck", "tarot.deck", "uno.deck"] {
+        let spec = DeckSpec::load(&decks.join(file)).expect("bundled decks are valid");
+        let mut deck = spec.build().expect("bundled decks aren't empty");
+        deck.shuffle_seeded(7);
+        let hand: Vec<String> = deck.deal(5).unwrap().iter().map(|card| card.to_string()).collect();
+        println!("{} ({} cards): {:?}", spec.name, spec.size(), hand);
+    }
+    let euchre = DeckSpec::new("Euchre")
+        .suits(&["Clubs", "Diamonds", "Hearts", "Spades"])
+        .ranks(&["Nine", "Ten", "Jack", "Queen", "King", "Ace"])
+        .jokers(1);
+    println!("{} has {} cards", euchre.name, euchre.build().unwrap().remaining());
+    if let Err(reason_this_failed) = "suits = Cups, Coins\nranks = 1*x".parse::<DeckSpec>() {
+        println!("{}", reason_this_failed);
+    }
+    // Real money: shuffle with a secure rng, publish a commitment, reveal it after the hand
+    let mut deck = Deck::new();
+    let (committed, reveal) = commitment::secure_shuffle(&mut deck);
+    println!("Commitment: {}", committed);
+    let deals = vec![deck.deal(2).unwrap(), deck.deal(2).unwrap(), deck.deal(5).unwrap()];
+    let published: Reveal = reveal.to_string().parse().expect("reveal we just wrote should parse");
+    match commitment::verify(&committed, &published, &deals) {
+        Ok(()) => println!("Every deal matched the committed deck"),
+        Err(reason_this_failed) => println!("{}", reason_this_failed),
+    }
+    let mut swapped = deals.clone();
+    swapped.swap(0, 1); // Claim the second player got the first player's cards
+    if let Err(reason_this_failed) = commitment::verify(&committed, &published, &swapped) {
+        println!("{}", reason_this_failed);
+    }
+    let mut short = published.clone();
+    short.order.truncate(50); // Committing to a short deck would hide which cards were left out
+    if let Err(reason_this_failed) = commitment::verify(&short.commitment(), &short, &[]) {
+        println!("{}", reason_this_failed);
+    }
+    // Four players at a six seat table playing five card draw
+    let mut table = Table::new(Deck::with_seed(11), 6);
+    for name in ["Ann", "Ben", "Cat", "Dan"] {
+        table.sit(name, 20).unwrap();
+    }
+    let mut game = DrawPoker::new(2);
+    table.start_hand(&mut game).expect("four players with chips");
+    if let Err(reason_this_failed) = table.act(&mut game, 3, DrawAction::Fold) {
+        println!("{}", reason_this_failed); // Seat 1 is first, left of the button
+    }
+    let mut result = None;
+    while let Some(seat) = table.turn() {
+        let player = table.player(seat).unwrap();
+        let action: DrawAction = match seat {
+            2 => "fold".parse().unwrap(),
+            _ => "draw 0 1 2".parse().unwrap(),
+        };
+        println!("{}: {} -> {}", player.name, draw_poker::describe_hand(&player.hand), action);
+        result = table.act(&mut game, seat, action).unwrap();
+    }
+    let result = result.expect("the hand ended");
+    println!("Pot of {} to seats {:?}", result.pot, result.winners);
+    for (seat, player) in table.players() {
+        println!("  seat {} {}: {} chips", seat, player.name, player.chips);
+    }
+    // Save a game part way through, load it back and carry on
+    let mut deck = Deck::with_seed(8);
+    deck.shuffle();
+    let (north, south) = (deck.deal(5).unwrap(), deck.deal(5).unwrap());
+    let played = deck.deal(3).unwrap();
+    deck.discard(played);
+    let saved = SavedGame::capture(&deck, &[("North", &north), ("South", &south)]);
+    let folder = std::env::temp_dir();
+    saved.save_json(&folder.join("basics_save.json")).expect("temp folder is writable");
+    saved.save_binary(&folder.join("basics_save.bin")).expect("temp folder is writable");
+    let loaded = SavedGame::load(&folder.join("basics_save.bin")).expect("save we just wrote should load");
+    println!(
+        "Saved as {} bytes of JSON or {} bytes of binary, loads back the same: {}",
+        saved.to_json().len(),
+        saved.to_bytes().unwrap().len(),
+        loaded == saved
+    );
+    let (mut restored, hands) = loaded.restore().unwrap();
+    deck.shuffle();
+    restored.shuffle();
+    println!(
+        "{} and {} kept their hands, the next shuffle matches: {}",
+        hands[0].name,
+        hands[1].name,
+        restored.cards() == deck.cards()
+    );
+    // Change one of North's cards into one South already has
+    let (stolen, copied) = (format!("\"{}\"", north[0].code()), format!("\"{}\"", south[0].code()));
+    let cheat = saved.to_json().replacen(&stolen, &copied, 1);
+    if let Err(reason_this_failed) = SavedGame::from_json(&cheat) {
+        println!("{}", reason_this_failed);
+    }
+    // The same hand in every card style
+    let mut deck = Deck::with_seed(3);
+    deck.shuffle();
+    let hand = deck.deal(4).unwrap();
+    for style in Style::ALL {
+        let renderer = style.renderer();
+        if renderer.multiline() {
+            println!("{}:\n{}", style, renderer.hand_with_backs(&hand, 1));
+        } else {
+            println!("{}: {}", style, renderer.hand_with_backs(&hand, 1));
+        }
+    }
+    // Four bridge boards, written as PBN and read back
+    let mut boards = vec![];
+    for board in 1..=4 {
+        let mut deck = Deck::with_seed(board as u64);
+        deck.shuffle();
+        boards.push(BridgeDeal::deal(&mut deck, board).unwrap());
+    }
+    println!("{}", boards[0]);
+    let pbn = bridge::write_pbn(&boards);
+    println!("{}", pbn);
+    println!("PBN reads back the same: {}", bridge::read_pbn(&pbn).unwrap() == boards);
+    let lin = boards[1].to_lin();
+    println!("{}\nLIN reads back the same: {}", lin, BridgeDeal::from_lin(&lin).unwrap() == boards[1]);
+    // What's left after a few cards, and what's left once a card we saw elsewhere is taken out too
+    let mut deck = Deck::with_seed(8);
+    deck.shuffle();
+    let dealt = deck.deal(6).unwrap();
+    let odds = deck.odds();
+    let codes: Vec<String> = dealt.iter().map(|card| card.code()).collect();
+    println!(
+        "After {}: {} cards left, next a Heart {:.3}, a King {:.3}, at least two Aces in the next 10 {:.3}",
+        codes.join(" "),
+        odds.total(),
+        odds.probability_suit(card::Suit::Hearts),
+        odds.probability_rank(card::Rank::King),
+        odds.chance_of_at_least(|card| card.rank == card::Rank::Ace, 2, 10)
+    );
+    let exposed = *deck.peek().unwrap();
+    let given = odds.given(&[exposed]).unwrap();
+    println!(
+        "Knowing {} is out: next a King {:.3}. Running count {}, true count {:.2}",
+        exposed.code(),
+        given.probability_rank(card::Rank::King),
+        deck.running_count().expect("with_seed() starts with the whole deck"),
+        deck.true_count().expect("with_seed() starts with the whole deck")
+    );
+    if let Err(reason_this_failed) = odds.given(&dealt[..1]) {
+        println!("{}", reason_this_failed);
+    }
+    // Hand histories: a bot match written out and read back, then a hold'em hand stepped through
+    let played = draw_poker::heads_up([("Groups", &KeepGroups), ("Pat", &StandPat)], 100, 5, 3, 21, 1_792_418_631);
+    let text = hand_history::write_all(&played.histories);
+    println!("{}", played.histories[0]);
+    println!("Reads back the same: {}", hand_history::parse_all(&text).unwrap() == played.histories);
+    let holdem = "PokerStars Hand #7: Hold'em No Limit (5/10) - 2026/10/20 00:15:00 UTC
+Table 'Club' 6-max Seat #1 is the button
+Seat 1: Ann (500 in chips)
+Seat 2: Bo (500 in chips)
+Ann: posts small blind 5
+Bo: posts big blind 10
+*** HOLE CARDS ***
+Dealt to Ann [Ah Kh]
+Dealt to Bo [7c 7d]
+Ann: calls 5
+Bo: checks
+*** FLOP *** [Qh Jh 2c]
+Bo: bets 20
+Ann: raises 40 to 60
+Bo: calls 40
+*** TURN *** [Qh Jh 2c] [Th]
+Bo: checks
+Ann: bets 100
+Bo: folds
+Uncalled bet (100) returned to Ann
+Ann collected 140 from pot
+*** SUMMARY ***
+Total pot 140 | Rake 0";
+    let hand: HandHistory = holdem.parse().unwrap();
+    let mut replayer = Replayer::new(&hand);
+    while replayer.forward().is_some() {}
+    replayer.back();
+    print!("Before the last event: {}", replayer.state());
+    println!("Round trip: {}", hand.to_text().parse::<HandHistory>().unwrap() == hand);
+    if let Err(reason_this_failed) = holdem.replace("Bo: checks", "Cy: checks").parse::<HandHistory>() {
+        println!("{}", reason_this_failed);
+    }
+}
@@ -0,0 +1,161 @@
+use super::card::{Card, Rank, Suit};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/**
+What's left to draw, and the chances of drawing it
+
+- Odds counts each card still in a deck or shoe, several copies when there are several decks
+- probability_*() is the chance the very next card matches, chance_of_at_least() looks further ahead
+  (the hypergeometric distribution, drawing without putting cards back)
+- given() is the same question after removing cards you know about but that are still in the deck
+  as far as it's concerned, e.g. another player's exposed card, or a card you saw burned
+- Hi-Lo: 2 to 6 count +1, 7 to 9 count 0, tens, faces and Aces count -1. The running count adds up
+  every card seen, the true count divides it by the decks still to come. High true counts mean the
+  remaining cards are rich in tens and Aces, which favours the blackjack player
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum OddsError {
+    NotInDeck(Card),
+}
+
+impl fmt::Display for OddsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OddsError::NotInDeck(card) => write!(f, "{} isn't left in the deck", card),
+        }
+    }
+}
+
+impl std::error::Error for OddsError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Odds {
+    counts: BTreeMap<Card, usize>,
+    total: usize,
+}
+
+impl Odds {
+    pub fn new(cards: &[Card]) -> Self {
+        let mut counts = BTreeMap::new();
+        for card in cards {
+            *counts.entry(*card).or_insert(0) += 1;
+        }
+        Odds {
+            counts,
+            total: cards.len(),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn count(&self, matches: impl Fn(&Card) -> bool) -> usize {
+        self.counts.iter().filter(|(card, _)| matches(card)).map(|(_, count)| count).sum()
+    }
+
+    pub fn count_rank(&self, rank: Rank) -> usize {
+        self.count(|card| card.rank == rank)
+    }
+
+    pub fn count_suit(&self, suit: Suit) -> usize {
+        self.count(|card| card.suit == suit)
+    }
+
+    pub fn count_card(&self, card: Card) -> usize {
+        self.counts.get(&card).copied().unwrap_or(0)
+    }
+
+    /// The chance the next card matches, 0 when nothing is left
+    pub fn probability(&self, matches: impl Fn(&Card) -> bool) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.count(matches) as f64 / self.total as f64
+    }
+
+    pub fn probability_rank(&self, rank: Rank) -> f64 {
+        self.probability(|card| card.rank == rank)
+    }
+
+    pub fn probability_suit(&self, suit: Suit) -> f64 {
+        self.probability(|card| card.suit == suit)
+    }
+
+    pub fn probability_card(&self, card: Card) -> f64 {
+        self.probability(|other| *other == card)
+    }
+
+    /// The chance that `draws` more cards hold at least `wanted` matching ones.
+    /// Draws past the last card are never made
+    pub fn chance_of_at_least(&self, matches: impl Fn(&Card) -> bool, wanted: usize, draws: usize) -> f64 {
+        let hits = self.count(matches);
+        let draws = draws.min(self.total);
+        (wanted..=draws.min(hits))
+            .map(|exactly| {
+                // Ways to pick the hits times ways to pick the misses, over all ways to pick the draws
+                (ln_choose(hits, exactly) + ln_choose(self.total - hits, draws - exactly)
+                    - ln_choose(self.total, draws))
+                .exp()
+            })
+            .sum::<f64>()
+            .min(1.0)
+    }
+
+    pub fn remove(&mut self, card: Card) -> Result<(), OddsError> {
+        match self.counts.get_mut(&card) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                self.total -= 1;
+                Ok(())
+            }
+            _ => Err(OddsError::NotInDeck(card)),
+        }
+    }
+
+    /// The odds once `known` cards are out of the way
+    pub fn given(&self, known: &[Card]) -> Result<Odds, OddsError> {
+        let mut odds = self.clone();
+        for card in known {
+            odds.remove(*card)?;
+        }
+        Ok(odds)
+    }
+
+    /// Hi-Lo total of the cards left. The negative of the running count for a complete pack
+    pub fn hi_lo_total(&self) -> i32 {
+        self.counts.iter().map(|(card, count)| hi_lo(card.rank) * *count as i32).sum()
+    }
+}
+
+/// The Hi-Lo value of one card
+pub fn hi_lo(rank: Rank) -> i32 {
+    match rank.value() {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1,
+    }
+}
+
+/// Hi-Lo running count of the cards seen, in any order
+pub fn running_count(seen: &[Card]) -> i32 {
+    seen.iter().map(|card| hi_lo(card.rank)).sum()
+}
+
+/// Running count per deck still to be dealt. 0 once there are no cards left
+pub fn true_count(running_count: i32, cards_left: usize) -> f64 {
+    if cards_left == 0 {
+        return 0.0;
+    }
+    running_count as f64 / (cards_left as f64 / 52.0)
+}
+
+// ln(n choose k), as a sum so big shoes don't overflow
+fn ln_choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (0..k).map(|i| ((n - i) as f64 / (i + 1) as f64).ln()).sum()
+}
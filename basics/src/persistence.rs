@@ -0,0 +1,279 @@
+use super::card::Card;
+use super::deck::Deck;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/**
+Saving a game in progress: the deck, the discard pile and everyone's hands
+
+- JSON for people to read, cards as their short codes ("AS"). Binary for size, one byte per card
+- Loading checks the cards between them make exactly one standard deck, nothing duplicated
+  or missing, so a hand edited in a text editor can't conjure a fifth Ace
+- A seeded deck saves its rng position too, so after loading it shuffles exactly as it would have
+- load() tells the formats apart by the binary's magic bytes
+
+Binary layout, counts are one byte:
+  "DECK" 1 (version)
+  0, or 1 then seed (u64) and rng seed (32 bytes), stream (u64), word position (u128), all little endian,
+  or 2 then just the seed (u64)
+  deck count, cards / discards count, cards
+  hand count, then per hand: name length, name (UTF-8), card count, cards
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedHand {
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub deck: Vec<Card>, // Bottom first, the top card last
+    pub discards: Vec<Card>,
+    pub hands: Vec<SavedHand>,
+    pub seed: Option<u64>,
+    pub rng: Option<RngState>,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Json(String),
+    Corrupt(String),
+    DuplicateCard(Card),
+    MissingCards(Vec<Card>),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "{}", err),
+            SaveError::Json(reason) => write!(f, "bad JSON save: {}", reason),
+            SaveError::Corrupt(reason) => write!(f, "corrupt save: {}", reason),
+            SaveError::DuplicateCard(card) => write!(f, "the save has {} more than once", card),
+            SaveError::MissingCards(cards) => {
+                let codes: Vec<String> = cards.iter().map(|card| card.code()).collect();
+                write!(f, "the save is missing {}", codes.join(" "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+const MAGIC: &[u8; 4] = b"DECK";
+const VERSION: u8 = 1;
+
+impl SavedGame {
+    pub fn capture(deck: &Deck, hands: &[(&str, &[Card])]) -> Self {
+        SavedGame {
+            deck: deck.cards().to_vec(),
+            discards: deck.discards().to_vec(),
+            hands: hands
+                .iter()
+                .map(|(name, cards)| SavedHand {
+                    name: name.to_string(),
+                    cards: cards.to_vec(),
+                })
+                .collect(),
+            seed: deck.seed(),
+            rng: deck.rng().map(|rng| RngState {
+                seed: rng.get_seed(),
+                stream: rng.get_stream(),
+                word_pos: rng.get_word_pos(),
+            }),
+        }
+    }
+
+    /// Every card of a standard deck exactly once, across the deck, discards and hands
+    pub fn validate(&self) -> Result<(), SaveError> {
+        let mut seen = HashSet::new();
+        let hands = self.hands.iter().flat_map(|hand| &hand.cards);
+        for card in self.deck.iter().chain(&self.discards).chain(hands) {
+            if !seen.insert(*card) {
+                return Err(SaveError::DuplicateCard(*card));
+            }
+        }
+        let missing: Vec<Card> = Deck::new().into_cards().into_iter().filter(|card| !seen.contains(card)).collect();
+        if !missing.is_empty() {
+            return Err(SaveError::MissingCards(missing));
+        }
+        Ok(())
+    }
+
+    /// The deck back as it was, and the hands
+    pub fn restore(self) -> Result<(Deck, Vec<SavedHand>), SaveError> {
+        self.validate()?;
+        let rng = self.rng.map(|state| {
+            let mut rng: ChaCha8Rng = rand::SeedableRng::from_seed(state.seed);
+            rng.set_stream(state.stream);
+            rng.set_word_pos(state.word_pos);
+            rng
+        });
+        Ok((Deck::from_parts(self.deck, self.discards, self.seed, rng), self.hands))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("cards, names and numbers always serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SaveError> {
+        let saved: SavedGame = serde_json::from_str(text).map_err(|err| SaveError::Json(err.to_string()))?;
+        saved.validate()?;
+        Ok(saved)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SaveError> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        match (self.seed, &self.rng) {
+            (Some(seed), Some(rng)) => {
+                bytes.push(1);
+                bytes.extend(seed.to_le_bytes());
+                bytes.extend(rng.seed);
+                bytes.extend(rng.stream.to_le_bytes());
+                bytes.extend(rng.word_pos.to_le_bytes());
+            }
+            (Some(seed), None) => {
+                bytes.push(2);
+                bytes.extend(seed.to_le_bytes());
+            }
+            (None, Some(_)) => return Err(SaveError::Corrupt(String::from("an rng position without its seed"))),
+            (None, None) => bytes.push(0),
+        }
+        write_cards(&mut bytes, &self.deck)?;
+        write_cards(&mut bytes, &self.discards)?;
+        bytes.push(count(self.hands.len(), "hands")?);
+        for hand in &self.hands {
+            bytes.push(count(hand.name.len(), "bytes in a name")?);
+            bytes.extend(hand.name.as_bytes());
+            write_cards(&mut bytes, &hand.cards)?;
+        }
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SaveError> {
+        let mut reader = Reader { bytes, at: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(SaveError::Corrupt(String::from("not a binary save")));
+        }
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(SaveError::Corrupt(format!("unknown version {}", version)));
+        }
+
+        let (seed, rng) = match reader.byte()? {
+            0 => (None, None),
+            1 => {
+                let seed = u64::from_le_bytes(reader.take(8)?.try_into().unwrap());
+                let rng = RngState {
+                    seed: reader.take(32)?.try_into().unwrap(),
+                    stream: u64::from_le_bytes(reader.take(8)?.try_into().unwrap()),
+                    word_pos: u128::from_le_bytes(reader.take(16)?.try_into().unwrap()),
+                };
+                (Some(seed), Some(rng))
+            }
+            2 => (Some(u64::from_le_bytes(reader.take(8)?.try_into().unwrap())), None),
+            other => return Err(SaveError::Corrupt(format!("bad seed flag {}", other))),
+        };
+        let deck = reader.cards()?;
+        let discards = reader.cards()?;
+        let mut hands = vec![];
+        for _ in 0..reader.byte()? {
+            let length = reader.byte()? as usize;
+            let name = String::from_utf8(reader.take(length)?.to_vec())
+                .map_err(|_| SaveError::Corrupt(String::from("a hand's name isn't UTF-8")))?;
+            hands.push(SavedHand {
+                name,
+                cards: reader.cards()?,
+            });
+        }
+        if reader.at != bytes.len() {
+            return Err(SaveError::Corrupt(String::from("extra bytes at the end")));
+        }
+
+        let saved = SavedGame {
+            deck,
+            discards,
+            hands,
+            seed,
+            rng,
+        };
+        saved.validate()?;
+        Ok(saved)
+    }
+
+    pub fn save_json(&self, path: &Path) -> Result<(), SaveError> {
+        Ok(fs::write(path, self.to_json())?)
+    }
+
+    pub fn save_binary(&self, path: &Path) -> Result<(), SaveError> {
+        Ok(fs::write(path, self.to_bytes()?)?)
+    }
+
+    /// Either format
+    pub fn load(path: &Path) -> Result<Self, SaveError> {
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(MAGIC) {
+            SavedGame::from_bytes(&bytes)
+        } else {
+            let text = String::from_utf8(bytes).map_err(|_| SaveError::Json(String::from("not UTF-8")))?;
+            SavedGame::from_json(&text)
+        }
+    }
+}
+
+fn count(length: usize, what: &str) -> Result<u8, SaveError> {
+    u8::try_from(length).map_err(|_| SaveError::Corrupt(format!("more than 255 {}", what)))
+}
+
+fn write_cards(bytes: &mut Vec<u8>, cards: &[Card]) -> Result<(), SaveError> {
+    bytes.push(count(cards.len(), "cards")?);
+    bytes.extend(cards.iter().map(Card::index));
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SaveError> {
+        let taken = self
+            .bytes
+            .get(self.at..self.at + length)
+            .ok_or(SaveError::Corrupt(String::from("ends too soon")))?;
+        self.at += length;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, SaveError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn cards(&mut self) -> Result<Vec<Card>, SaveError> {
+        let length = self.byte()? as usize;
+        self.take(length)?
+            .iter()
+            .map(|index| Card::from_index(*index).ok_or(SaveError::Corrupt(format!("{} isn't a card", index))))
+            .collect()
+    }
+}
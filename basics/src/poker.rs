@@ -0,0 +1,207 @@
+use super::card::{Card, Rank};
+use std::fmt;
+
+/**
+Poker hand evaluation
+
+- A hand's strength is packed into one u32: category in the top bits, then up to five ranks
+  four bits each, most important first. Comparing two hands is one integer comparison
+- Ranks are ordered for tie-breaks: the grouped cards first (the pair in a one pair hand) then the kickers high to low
+- Ace plays low only in the wheel (A 2 3 4 5), where the straight's top card is the Five
+- best_of() tries every 5 card subset, so 7 cards (Texas Hold'em) is 21 evaluations
+- Cards dealt from a multi-deck Shoe can repeat. Five of a kind beats a straight flush, and a suited hand
+  with repeated ranks is a flush unless its pairs make something better (a full house or four of a kind)
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind, // Only possible with more than one deck
+}
+
+impl Category {
+    pub const ALL: [Category; 10] = [
+        Category::HighCard,
+        Category::OnePair,
+        Category::TwoPair,
+        Category::ThreeOfAKind,
+        Category::Straight,
+        Category::Flush,
+        Category::FullHouse,
+        Category::FourOfAKind,
+        Category::StraightFlush,
+        Category::FiveOfAKind,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::HighCard => "High Card",
+            Category::OnePair => "One Pair",
+            Category::TwoPair => "Two Pair",
+            Category::ThreeOfAKind => "Three of a Kind",
+            Category::Straight => "Straight",
+            Category::Flush => "Flush",
+            Category::FullHouse => "Full House",
+            Category::FourOfAKind => "Four of a Kind",
+            Category::StraightFlush => "Straight Flush",
+            Category::FiveOfAKind => "Five of a Kind",
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    pub fn category(&self) -> Category {
+        Category::ALL[(self.0 >> 20) as usize]
+    }
+
+    /// Tie-break ranks in the order they're compared
+    pub fn kickers(&self) -> Vec<Rank> {
+        (0..5)
+            .rev()
+            .map(|slot| (self.0 >> (slot * 4)) & 0xF)
+            .filter(|value| *value != 0)
+            .map(|value| Rank::from_value(value as u8).unwrap())
+            .collect()
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kickers: Vec<&str> = self.kickers().iter().map(|rank| rank.code()).collect();
+        write!(f, "{} ({})", self.category(), kickers.join(" "))
+    }
+}
+
+fn pack(category: Category, ranks: &[u8]) -> HandRank {
+    let mut value = (category as u32) << 20;
+    for (slot, rank) in ranks.iter().enumerate() {
+        value |= (*rank as u32) << ((4 - slot) * 4);
+    }
+    HandRank(value)
+}
+
+const WHEEL: u16 = 1 << 14 | 1 << 5 | 1 << 4 | 1 << 3 | 1 << 2; // A 5 4 3 2
+
+pub fn evaluate_5(cards: &[Card; 5]) -> HandRank {
+    let mut counts = [0u8; 15]; // Indexed by rank value, 2..=14
+    let mut mask = 0u16;
+    for card in cards {
+        counts[card.rank.value() as usize] += 1;
+        mask |= 1 << card.rank.value();
+    }
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+
+    // Five different ranks: straight, flush or high card
+    if mask.count_ones() == 5 {
+        let high = 15 - mask.leading_zeros() as u8;
+        let low = mask.trailing_zeros() as u8;
+        let straight_high = if high - low == 4 {
+            Some(high)
+        } else if mask == WHEEL {
+            Some(Rank::Five.value())
+        } else {
+            None
+        };
+
+        return match (straight_high, flush) {
+            (Some(high), true) => pack(Category::StraightFlush, &[high]),
+            (Some(high), false) => pack(Category::Straight, &[high]),
+            (None, true) => pack(Category::Flush, &ranks_high_to_low(&counts)),
+            (None, false) => pack(Category::HighCard, &ranks_high_to_low(&counts)),
+        };
+    }
+
+    // Some ranks repeat. Order ranks by how many there are, then by rank
+    // Fixed size arrays rather than Vecs, this runs millions of times
+    let mut groups = [(0u8, 0u8); 4]; // (count, rank), at most 4 different ranks once one repeats
+    let mut found = 0;
+    for rank in (2..=14u8).rev() {
+        if counts[rank as usize] > 0 {
+            groups[found] = (counts[rank as usize], rank);
+            found += 1;
+        }
+    }
+    groups[..found].sort_unstable_by(|a, b| b.cmp(a));
+
+    let category = match (groups[0].0, groups[1].0) {
+        (5, _) => Category::FiveOfAKind,
+        (4, _) => Category::FourOfAKind,
+        (3, 2) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        _ => Category::OnePair,
+    };
+    // Repeated ranks in one suit need repeated cards, from more than one deck
+    if flush && category < Category::Flush {
+        let mut ranks = cards.map(|card| card.rank.value());
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+        return pack(Category::Flush, &ranks);
+    }
+    pack(category, &groups.map(|(_, rank)| rank)[..found])
+}
+
+fn ranks_high_to_low(counts: &[u8; 15]) -> [u8; 5] {
+    let mut ranks = [0u8; 5];
+    let mut found = 0;
+    for rank in (2..=14u8).rev() {
+        if counts[rank as usize] > 0 && found < 5 {
+            ranks[found] = rank;
+            found += 1;
+        }
+    }
+    ranks
+}
+
+/// Best 5 card hand out of 5 or more cards, None if there are fewer than 5
+pub fn best_of(cards: &[Card]) -> Option<HandRank> {
+    if cards.len() < 5 {
+        return None;
+    }
+
+    let mut best = None;
+    let mut chosen = [0usize, 1, 2, 3, 4];
+    loop {
+        let hand = chosen.map(|index| cards[index]);
+        let rank = evaluate_5(&hand);
+        if best.is_none_or(|best| rank > best) {
+            best = Some(rank);
+        }
+
+        // Next combination in lexicographic order
+        let mut slot = 5;
+        while slot > 0 && chosen[slot - 1] == cards.len() - 5 + slot - 1 {
+            slot -= 1;
+        }
+        if slot == 0 {
+            return best;
+        }
+        chosen[slot - 1] += 1;
+        for next in slot..5 {
+            chosen[next] = chosen[next - 1] + 1;
+        }
+    }
+}
+
+/// Texas Hold'em: two hole cards plus the five card board
+pub fn holdem(hole: &[Card; 2], board: &[Card; 5]) -> HandRank {
+    let mut cards = [hole[0]; 7];
+    cards[1] = hole[1];
+    cards[2..].copy_from_slice(board);
+    best_of(&cards).unwrap()
+}
@@ -0,0 +1,249 @@
+use super::card::{Card, Rank, Suit};
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Drawing cards in a terminal
+
+- Text: "Ace of Spades". Codes: "AS"
+- Unicode: the playing card characters (U+1F0A1 and on). Needs a font that has them
+- Color: "A♠" with hearts and diamonds in red, using ANSI escape codes
+- Ascii: little boxes, several lines tall, laid side by side. Plain ASCII so any terminal shows it,
+  AsciiColor adds the red
+- Pick one at runtime with Style, e.g. "--cards unicode".parse::<Style>()
+*/
+pub trait Renderer {
+    fn card(&self, card: &Card) -> String;
+
+    /// A face down card
+    fn back(&self) -> String;
+
+    /// Puts cards drawn by card() or back() next to each other
+    fn layout(&self, pieces: Vec<String>) -> String {
+        pieces.join(" ")
+    }
+
+    /// True when a hand takes more than one line, so it needs a line of its own
+    fn multiline(&self) -> bool {
+        false
+    }
+
+    fn hand(&self, cards: &[Card]) -> String {
+        self.layout(cards.iter().map(|card| self.card(card)).collect())
+    }
+
+    /// The cards, then `hidden` face down cards after them
+    fn hand_with_backs(&self, shown: &[Card], hidden: usize) -> String {
+        let mut pieces: Vec<String> = shown.iter().map(|card| self.card(card)).collect();
+        pieces.extend((0..hidden).map(|_| self.back()));
+        self.layout(pieces)
+    }
+}
+
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+fn is_red(suit: Suit) -> bool {
+    matches!(suit, Suit::Diamonds | Suit::Hearts)
+}
+
+fn symbol(suit: Suit) -> char {
+    match suit {
+        Suit::Clubs => '♣',
+        Suit::Diamonds => '♦',
+        Suit::Hearts => '♥',
+        Suit::Spades => '♠',
+    }
+}
+
+// "10" rather than Rank::code()'s "T", there's room for it when drawing
+fn face(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Ten => "10",
+        rank => rank.code(),
+    }
+}
+
+pub struct Text;
+
+impl Renderer for Text {
+    fn card(&self, card: &Card) -> String {
+        card.to_string()
+    }
+
+    fn back(&self) -> String {
+        String::from("a face down card")
+    }
+
+    fn layout(&self, pieces: Vec<String>) -> String {
+        pieces.join(", ")
+    }
+}
+
+pub struct Codes;
+
+impl Renderer for Codes {
+    fn card(&self, card: &Card) -> String {
+        card.code()
+    }
+
+    fn back(&self) -> String {
+        String::from("??")
+    }
+}
+
+pub struct Unicode;
+
+impl Renderer for Unicode {
+    fn card(&self, card: &Card) -> String {
+        let suit_base = match card.suit {
+            Suit::Spades => 0x1F0A0,
+            Suit::Hearts => 0x1F0B0,
+            Suit::Diamonds => 0x1F0C0,
+            Suit::Clubs => 0x1F0D0,
+        };
+        // Ace is 1, then 2 to 10 in order. 0xC is the Knight from tarot decks, skipped
+        let rank = match card.rank {
+            Rank::Ace => 0x1,
+            Rank::Jack => 0xB,
+            Rank::Queen => 0xD,
+            Rank::King => 0xE,
+            rank => rank.value() as u32,
+        };
+        char::from_u32(suit_base + rank).unwrap().to_string()
+    }
+
+    fn back(&self) -> String {
+        String::from('\u{1F0A0}')
+    }
+}
+
+pub struct Color;
+
+impl Renderer for Color {
+    fn card(&self, card: &Card) -> String {
+        let text = format!("{}{}", face(card.rank), symbol(card.suit));
+        if is_red(card.suit) {
+            format!("{}{}{}", RED, text, RESET)
+        } else {
+            text
+        }
+    }
+
+    fn back(&self) -> String {
+        String::from("##")
+    }
+}
+
+pub struct AsciiArt {
+    pub color: bool,
+}
+
+const BORDER: &str = "+-----+";
+
+impl Renderer for AsciiArt {
+    fn card(&self, card: &Card) -> String {
+        let (face, suit) = (face(card.rank), card.suit.letter());
+        let (start, end) = if self.color && is_red(card.suit) { (RED, RESET) } else { ("", "") };
+        [
+            BORDER.to_string(),
+            format!("|{}{:<5}{}|", start, face, end),
+            format!("|{}  {}  {}|", start, suit, end),
+            format!("|{}{:>5}{}|", start, face, end),
+            BORDER.to_string(),
+        ]
+        .join("\n")
+    }
+
+    fn back(&self) -> String {
+        [BORDER, "|#####|", "|#####|", "|#####|", BORDER].join("\n")
+    }
+
+    fn layout(&self, pieces: Vec<String>) -> String {
+        let drawn: Vec<Vec<&str>> = pieces.iter().map(|piece| piece.lines().collect()).collect();
+        let height = drawn.iter().map(Vec::len).max().unwrap_or(0);
+        (0..height)
+            .map(|line| {
+                let row: Vec<&str> = drawn.iter().map(|piece| piece.get(line).copied().unwrap_or("")).collect();
+                row.join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn multiline(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Text,
+    Codes,
+    Unicode,
+    Color,
+    Ascii,
+    AsciiColor,
+}
+
+impl Style {
+    pub const ALL: [Style; 6] = [
+        Style::Text,
+        Style::Codes,
+        Style::Unicode,
+        Style::Color,
+        Style::Ascii,
+        Style::AsciiColor,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Style::Text => "text",
+            Style::Codes => "codes",
+            Style::Unicode => "unicode",
+            Style::Color => "color",
+            Style::Ascii => "ascii",
+            Style::AsciiColor => "ascii-color",
+        }
+    }
+
+    pub fn renderer(&self) -> Box<dyn Renderer> {
+        match self {
+            Style::Text => Box::new(Text),
+            Style::Codes => Box::new(Codes),
+            Style::Unicode => Box::new(Unicode),
+            Style::Color => Box::new(Color),
+            Style::Ascii => Box::new(AsciiArt { color: false }),
+            Style::AsciiColor => Box::new(AsciiArt { color: true }),
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownStyle(pub String);
+
+impl fmt::Display for UnknownStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = Style::ALL.iter().map(Style::name).collect();
+        write!(f, "'{}' isn't a card style, try one of {}", self.0, names.join(", "))
+    }
+}
+
+impl std::error::Error for UnknownStyle {}
+
+impl FromStr for Style {
+    type Err = UnknownStyle;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Style::ALL
+            .into_iter()
+            .find(|style| style.name().eq_ignore_ascii_case(text.trim()))
+            .ok_or_else(|| UnknownStyle(text.to_string()))
+    }
+}
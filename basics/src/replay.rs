@@ -0,0 +1,241 @@
+use super::card::Card;
+use super::deck::{DealError, Deck};
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Recording and replaying games
+- A game is a seed plus the list of things that were done to the deck
+- Replaying the same seed and actions deals exactly the same cards
+- Saved as plain text, the seed then one action per line:
+  seed 42 / shuffle / deal 5 / deal-up-to 5 / draw / discard AS KH / reshuffle
+- Dealt cards are out in the players' hands until they're discarded. Only those can be discarded,
+  so a log can't slip in cards that were never dealt or discard a card twice
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Shuffle,
+    Deal(usize),
+    DealUpTo(usize),
+    Draw,
+    Discard(Vec<Card>),
+    ReshuffleDiscards,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    Deal(DealError),
+    NotInHand(Card),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Deal(reason) => write!(f, "{}", reason),
+            ReplayError::NotInHand(card) => write!(f, "can't discard {}, nobody is holding it", card),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<DealError> for ReplayError {
+    fn from(err: DealError) -> Self {
+        ReplayError::Deal(err)
+    }
+}
+
+impl Action {
+    /// Applies the action and returns the cards it dealt, if any. `held` is every card dealt and not
+    /// yet discarded, dealt cards join it and discarded ones must come out of it. Nothing changes on an error
+    pub fn apply(&self, deck: &mut Deck, held: &mut Vec<Card>) -> Result<Vec<Card>, ReplayError> {
+        let dealt = match self {
+            Action::Shuffle => {
+                deck.shuffle();
+                vec![]
+            }
+            Action::Deal(num_cards) => deck.deal(*num_cards)?,
+            Action::DealUpTo(num_cards) => deck.deal_up_to(*num_cards),
+            Action::Draw => deck.draw_one().into_iter().collect(),
+            Action::Discard(cards) => {
+                let mut still_held = held.clone();
+                for card in cards {
+                    match still_held.iter().position(|held_card| held_card == card) {
+                        Some(index) => still_held.swap_remove(index),
+                        None => return Err(ReplayError::NotInHand(*card)),
+                    };
+                }
+                *held = still_held;
+                deck.discard(cards.clone());
+                vec![]
+            }
+            Action::ReshuffleDiscards => {
+                deck.reshuffle_discards();
+                vec![]
+            }
+        };
+        held.extend(dealt.iter().copied());
+        Ok(dealt)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Shuffle => write!(f, "shuffle"),
+            Action::Deal(num_cards) => write!(f, "deal {}", num_cards),
+            Action::DealUpTo(num_cards) => write!(f, "deal-up-to {}", num_cards),
+            Action::Draw => write!(f, "draw"),
+            Action::Discard(cards) => {
+                let codes: Vec<String> = cards.iter().map(|card| card.code()).collect();
+                write!(f, "discard {}", codes.join(" "))
+            }
+            Action::ReshuffleDiscards => write!(f, "reshuffle"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let command = words.next().ok_or("empty line")?;
+        let rest: Vec<&str> = words.collect();
+
+        let count = || -> Result<usize, String> {
+            match rest.as_slice() {
+                [n] => n.parse().map_err(|_| format!("'{}' is not a number of cards", n)),
+                _ => Err(format!("'{}' takes one number", command)),
+            }
+        };
+
+        match command {
+            "shuffle" => Ok(Action::Shuffle),
+            "deal" => Ok(Action::Deal(count()?)),
+            "deal-up-to" => Ok(Action::DealUpTo(count()?)),
+            "draw" => Ok(Action::Draw),
+            "discard" => rest
+                .iter()
+                .map(|code| code.parse::<Card>().map_err(|err| err.to_string()))
+                .collect::<Result<Vec<Card>, String>>()
+                .map(Action::Discard),
+            "reshuffle" => Ok(Action::ReshuffleDiscards),
+            _ => Err(format!("unknown action '{}'", command)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameLog {
+    pub seed: u64,
+    pub actions: Vec<Action>,
+}
+
+impl GameLog {
+    /// Plays the log back from a fresh deck. Returns the final deck and the cards each action dealt
+    pub fn replay(&self) -> Result<(Deck, Vec<Vec<Card>>), ReplayError> {
+        let mut deck = Deck::with_seed(self.seed);
+        let mut held = vec![];
+        let dealt = self
+            .actions
+            .iter()
+            .map(|action| action.apply(&mut deck, &mut held))
+            .collect::<Result<Vec<Vec<Card>>, ReplayError>>()?;
+        Ok((deck, dealt))
+    }
+}
+
+impl fmt::Display for GameLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "seed {}", self.seed)?;
+        for action in &self.actions {
+            writeln!(f, "{}", action)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseLogError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseLogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseLogError {}
+
+impl FromStr for GameLog {
+    type Err = ParseLogError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let seed = match lines.next() {
+            Some((line, text)) => text
+                .strip_prefix("seed ")
+                .and_then(|seed| seed.trim().parse().ok())
+                .ok_or(ParseLogError {
+                    line,
+                    reason: String::from("expected 'seed <number>' first"),
+                })?,
+            None => {
+                return Err(ParseLogError {
+                    line: 1,
+                    reason: String::from("empty game log"),
+                })
+            }
+        };
+
+        let actions = lines
+            .map(|(line, text)| text.parse().map_err(|reason| ParseLogError { line, reason }))
+            .collect::<Result<Vec<Action>, ParseLogError>>()?;
+
+        Ok(GameLog { seed, actions })
+    }
+}
+
+/// A seeded deck that writes down everything done to it
+#[derive(Debug)]
+pub struct RecordedGame {
+    deck: Deck,
+    held: Vec<Card>, // Dealt and not discarded yet
+    log: GameLog,
+}
+
+impl RecordedGame {
+    pub fn new(seed: u64) -> Self {
+        RecordedGame {
+            deck: Deck::with_seed(seed),
+            held: vec![],
+            log: GameLog {
+                seed,
+                actions: vec![],
+            },
+        }
+    }
+
+    /// Only actions that succeed are recorded, so the log always replays cleanly
+    pub fn play(&mut self, action: Action) -> Result<Vec<Card>, ReplayError> {
+        let dealt = action.apply(&mut self.deck, &mut self.held)?;
+        self.log.actions.push(action);
+        Ok(dealt)
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    pub fn log(&self) -> &GameLog {
+        &self.log
+    }
+}
@@ -0,0 +1,487 @@
+use super::card::Card;
+use super::deck::Deck;
+use super::draw_poker::{DrawAction, DrawPoker};
+use super::table::{Table, TableError, TableState};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/**
+Card game server, five card draw tables over TCP
+
+- One line per message, UTF-8, either \n or \r\n, at most 1024 bytes. Try it with `nc localhost 7878`
+- Every command gets exactly one reply line, `OK ...` or `ERR <reason>`
+- EVENT, CARDS and SEAT lines can arrive at any time, including just before a reply
+- The deck only exists on the server. Each player is sent their own cards and nothing else,
+  other hands are only shown at a showdown
+
+Commands
+  JOIN <table> <name>   sit at a table, made on first join. OK SEAT <seat> TOKEN <token>
+  RESUME <token>        take your seat back after a dropped connection. OK SEAT <seat> TABLE <table>
+  START                 deal a new hand. OK STARTED
+  ACT <action>          on your turn: stand, fold or draw <positions> (0-4, at most 3). OK ACTED
+  HAND                  OK CARDS <cards>, your cards as codes (AS, TD, 7H)
+  STATE                 a SEAT line per player, then OK STATE <waiting|in-hand> BUTTON <seat> TURN <seat|-> POT <chips>
+  LEAVE                 give up your seat, not allowed while you're in a hand. OK LEFT
+  QUIT                  close the connection but keep your seat for RESUME. OK BYE
+
+Sent by the server
+  CARDS <cards>                           your cards, after every deal or draw
+  SEAT <seat> <chips> <in|out> <online|away> <name>
+  EVENT JOINED <seat> <name>  /  EVENT LEFT <seat>  /  EVENT AWAY <seat>  /  EVENT BACK <seat>
+  EVENT STARTED BUTTON <seat> POT <chips>
+  EVENT TURN <seat>
+  EVENT ACTED <seat> <action>
+  EVENT SHOW <seat> <cards>
+  EVENT RESULT POT <chips> WINNERS <seat> ...
+  EVENT CHIPS <seat> <chips>
+
+Reconnecting
+- A dropped connection keeps its seat until the player comes back with RESUME and their token
+- The hand doesn't wait for them. Once it's been their turn and they've been away for 30 seconds
+  the server stands for them, EVENT ACTED <seat> stand like any other action
+- The token is the only proof of who you are, keep it private
+- A client that stops reading is dropped like any other lost connection, once its unread lines
+  pile up or a write to it stalls. It can RESUME when it's back
+*/
+pub const DEFAULT_PORT: u16 = 7878;
+const SEATS: usize = 6;
+const STARTING_CHIPS: i64 = 100;
+const ANTE: i64 = 2;
+const MAX_LINE: usize = 1024; // Bytes, a longer line closes the connection
+const OUTBOX_LINES: usize = 256; // Unsent lines a client can fall behind by
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+const AWAY_TURN: Duration = Duration::from_secs(30); // How long the table waits on a player who's away
+const TIMER_TICK: Duration = Duration::from_millis(100);
+
+struct Session {
+    table: String,
+    seat: usize,
+    outbox: Option<Outbox>, // None while disconnected
+    connection: u64,
+    away_since: Instant,    // Only meaningful while outbox is None
+}
+
+struct Hosted {
+    table: Table,
+    game: DrawPoker,
+    turn_since: Instant,
+}
+
+struct Lobby {
+    tables: HashMap<String, Hosted>,
+    sessions: HashMap<String, Session>, // By token
+    connections: u64,
+    away_turn: Duration,
+}
+
+// Everything a connection thread knows about itself
+struct Connection {
+    id: u64,
+    outbox: Outbox,
+    token: Option<String>,
+}
+
+// Lines waiting to go out on one connection. Its own thread writes them, so nothing ever blocks on
+// a socket with the lobby locked
+#[derive(Clone)]
+struct Outbox {
+    lines: SyncSender<String>,
+    stream: Arc<TcpStream>, // For shutting the connection down
+}
+
+impl Outbox {
+    fn start(stream: TcpStream) -> io::Result<Outbox> {
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let mut writer = stream.try_clone()?;
+        let (lines, queued) = mpsc::sync_channel::<String>(OUTBOX_LINES);
+        // Ends once every Outbox for the connection is dropped and the queue is sent
+        thread::spawn(move || {
+            for line in queued {
+                if writer.write_all(format!("{}\n", line).as_bytes()).is_err() {
+                    let _ = writer.shutdown(Shutdown::Both);
+                    break;
+                }
+            }
+        });
+        Ok(Outbox {
+            lines,
+            stream: Arc::new(stream),
+        })
+    }
+
+    // Never blocks. A connection that's gone or too far behind is shut down and this returns false
+    fn push(&self, line: String) -> bool {
+        if self.lines.try_send(line).is_ok() {
+            return true;
+        }
+        let _ = self.stream.shutdown(Shutdown::Both);
+        false
+    }
+}
+
+fn codes(cards: &[Card]) -> String {
+    let codes: Vec<String> = cards.iter().map(|card| card.code()).collect();
+    codes.join(" ")
+}
+
+impl Lobby {
+    fn new(away_turn: Duration) -> Self {
+        Lobby {
+            tables: HashMap::new(),
+            sessions: HashMap::new(),
+            connections: 0,
+            away_turn,
+        }
+    }
+
+    // Queued with the lobby locked, so every player sees events in the same order
+    fn send(&mut self, token: &str, line: &str) {
+        if let Some(session) = self.sessions.get_mut(token) {
+            if session.outbox.as_ref().is_some_and(|outbox| !outbox.push(line.to_string())) {
+                session.outbox = None;
+                session.away_since = Instant::now();
+            }
+        }
+    }
+
+    fn broadcast(&mut self, table: &str, line: &str) {
+        let tokens: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.table == table)
+            .map(|(token, _)| token.clone())
+            .collect();
+        for token in tokens {
+            self.send(&token, line);
+        }
+    }
+
+    fn token_for(&self, table: &str, seat: usize) -> Option<String> {
+        self.sessions
+            .iter()
+            .find(|(_, session)| session.table == table && session.seat == seat)
+            .map(|(token, _)| token.clone())
+    }
+
+    fn send_cards(&mut self, table: &str, seat: usize) {
+        let cards = match self.tables[table].table.player(seat) {
+            Ok(player) if player.in_hand => codes(&player.hand),
+            _ => return,
+        };
+        if let Some(token) = self.token_for(table, seat) {
+            self.send(&token, &format!("CARDS {}", cards));
+        }
+    }
+
+    // Also starts the turn's clock, so it's only called when the turn changes or someone comes back
+    fn announce_turn(&mut self, table: &str) {
+        let hosted = self.tables.get_mut(table).unwrap();
+        hosted.turn_since = Instant::now();
+        if let Some(seat) = hosted.table.turn() {
+            self.broadcast(table, &format!("EVENT TURN {}", seat));
+        }
+    }
+
+    // The table and seat of a connection that has joined
+    fn seated(&self, connection: &Connection) -> Result<(String, usize), String> {
+        let session = connection
+            .token
+            .as_ref()
+            .and_then(|token| self.sessions.get(token))
+            .ok_or("JOIN or RESUME first")?;
+        if session.connection != connection.id {
+            return Err(String::from("this seat was resumed from another connection"));
+        }
+        Ok((session.table.clone(), session.seat))
+    }
+
+    fn command(&mut self, connection: &mut Connection, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args = args.trim();
+
+        match command.to_ascii_uppercase().as_str() {
+            "JOIN" => {
+                if connection.token.is_some() {
+                    return Err(String::from("already seated, LEAVE first"));
+                }
+                let (table_name, name) = args
+                    .split_once(' ')
+                    .map(|(table, name)| (table, name.trim()))
+                    .filter(|(_, name)| !name.is_empty())
+                    .ok_or("usage: JOIN <table> <name>")?;
+                let hosted = self.tables.entry(table_name.to_string()).or_insert_with(|| Hosted {
+                    table: Table::new(Deck::new(), SEATS),
+                    game: DrawPoker::new(ANTE),
+                    turn_since: Instant::now(),
+                });
+                let seat = hosted.table.sit(name, STARTING_CHIPS).map_err(|err| err.to_string())?;
+
+                let token = format!("{:032x}", rand::random::<u128>());
+                self.sessions.insert(
+                    token.clone(),
+                    Session {
+                        table: table_name.to_string(),
+                        seat,
+                        outbox: Some(connection.outbox.clone()),
+                        connection: connection.id,
+                        away_since: Instant::now(),
+                    },
+                );
+                connection.token = Some(token.clone());
+                self.broadcast(table_name, &format!("EVENT JOINED {} {}", seat, name));
+                Ok(format!("SEAT {} TOKEN {}", seat, token))
+            }
+            "RESUME" => {
+                if connection.token.is_some() {
+                    return Err(String::from("already seated"));
+                }
+                let session = self.sessions.get_mut(args).ok_or("unknown token")?;
+                session.outbox = Some(connection.outbox.clone());
+                session.connection = connection.id;
+                let (table, seat) = (session.table.clone(), session.seat);
+                connection.token = Some(args.to_string());
+
+                self.broadcast(&table, &format!("EVENT BACK {}", seat));
+                self.send_cards(&table, seat);
+                self.announce_turn(&table);
+                Ok(format!("SEAT {} TABLE {}", seat, table))
+            }
+            "START" => {
+                let (table, _) = self.seated(connection)?;
+                let hosted = self.tables.get_mut(&table).unwrap();
+                hosted.table.start_hand(&mut hosted.game).map_err(|err| err.to_string())?;
+                let (button, pot, in_hand) = (hosted.table.button(), hosted.table.pot(), hosted.table.in_hand());
+
+                self.broadcast(&table, &format!("EVENT STARTED BUTTON {} POT {}", button, pot));
+                for seat in in_hand {
+                    self.send_cards(&table, seat);
+                }
+                self.announce_turn(&table);
+                Ok(String::from("STARTED"))
+            }
+            "ACT" => {
+                let (table, seat) = self.seated(connection)?;
+                let action: DrawAction = args.parse().map_err(|err: TableError| err.to_string())?;
+                self.act(&table, seat, action)?;
+                Ok(String::from("ACTED"))
+            }
+            "HAND" => {
+                let (table, seat) = self.seated(connection)?;
+                let player = self.tables[&table].table.player(seat).map_err(|err| err.to_string())?;
+                Ok(format!("CARDS {}", codes(&player.hand)))
+            }
+            "STATE" => {
+                let (table, _) = self.seated(connection)?;
+                let hosted = &self.tables[&table].table;
+                let lines: Vec<String> = hosted
+                    .players()
+                    .map(|(seat, player)| {
+                        let online = self
+                            .token_for(&table, seat)
+                            .is_some_and(|token| self.sessions[&token].outbox.is_some());
+                        format!(
+                            "SEAT {} {} {} {} {}",
+                            seat,
+                            player.chips,
+                            if player.in_hand { "in" } else { "out" },
+                            if online { "online" } else { "away" },
+                            player.name
+                        )
+                    })
+                    .collect();
+                let state = format!(
+                    "STATE {} BUTTON {} TURN {} POT {}",
+                    match hosted.state() {
+                        TableState::Waiting => "waiting",
+                        TableState::InHand => "in-hand",
+                    },
+                    hosted.button(),
+                    hosted.turn().map_or(String::from("-"), |seat| seat.to_string()),
+                    hosted.pot()
+                );
+                for line in lines {
+                    connection.outbox.push(line);
+                }
+                Ok(state)
+            }
+            "LEAVE" => {
+                let (table, seat) = self.seated(connection)?;
+                let hosted = self.tables.get_mut(&table).unwrap();
+                hosted.table.leave(seat).map_err(|err| err.to_string())?;
+                if hosted.table.players().next().is_none() {
+                    self.tables.remove(&table);
+                }
+
+                self.sessions.remove(&connection.token.take().unwrap());
+                self.broadcast(&table, &format!("EVENT LEFT {}", seat));
+                Ok(String::from("LEFT"))
+            }
+            "QUIT" => Ok(String::from("BYE")),
+            "" => Err(String::from("empty command")),
+            other => Err(format!("unknown command '{}'", other)),
+        }
+    }
+
+    // Plays an action and tells the table what happened, for ACT and for players who are away
+    fn act(&mut self, table: &str, seat: usize, action: DrawAction) -> Result<(), String> {
+        let shown = action.to_string();
+        let hosted = self.tables.get_mut(table).unwrap();
+        let result = hosted
+            .table
+            .act(&mut hosted.game, seat, action)
+            .map_err(|err| err.to_string())?;
+
+        self.broadcast(table, &format!("EVENT ACTED {} {}", seat, shown));
+        match result {
+            None => {
+                self.send_cards(table, seat);
+                self.announce_turn(table);
+            }
+            Some(result) => {
+                for (shown_seat, cards) in &result.shown {
+                    self.broadcast(table, &format!("EVENT SHOW {} {}", shown_seat, codes(cards)));
+                }
+                let winners: Vec<String> = result.winners.iter().map(|seat| seat.to_string()).collect();
+                self.broadcast(
+                    table,
+                    &format!("EVENT RESULT POT {} WINNERS {}", result.pot, winners.join(" ")),
+                );
+                let chips: Vec<(usize, i64)> = self.tables[table]
+                    .table
+                    .players()
+                    .map(|(seat, player)| (seat, player.chips))
+                    .collect();
+                for (seat, chips) in chips {
+                    self.broadcast(table, &format!("EVENT CHIPS {} {}", seat, chips));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Stands for every player whose turn it is and who has been away too long. The wait runs from
+    // whichever came last, the turn starting or the player leaving
+    fn act_for_away(&mut self) {
+        let now = Instant::now();
+        let mut overdue = vec![];
+        for (name, hosted) in &self.tables {
+            let Some(seat) = hosted.table.turn() else {
+                continue;
+            };
+            let away = self
+                .sessions
+                .values()
+                .find(|session| &session.table == name && session.seat == seat && session.outbox.is_none());
+            if let Some(session) = away {
+                if now.duration_since(session.away_since.max(hosted.turn_since)) >= self.away_turn {
+                    overdue.push((name.clone(), seat));
+                }
+            }
+        }
+        for (table, seat) in overdue {
+            let _ = self.act(&table, seat, DrawAction::Draw(vec![])); // Standing pat is always allowed on your turn
+        }
+    }
+
+    fn disconnect(&mut self, connection: &Connection) {
+        let Some(token) = connection.token.as_ref() else {
+            return;
+        };
+        // A RESUME from a newer connection owns the session now
+        let Some(session) = self.sessions.get_mut(token).filter(|session| session.connection == connection.id) else {
+            return;
+        };
+        session.outbox = None;
+        session.away_since = Instant::now();
+        let (table, seat) = (session.table.clone(), session.seat);
+        self.broadcast(&table, &format!("EVENT AWAY {}", seat));
+    }
+}
+
+// The next line without its line ending, None once the client has closed the connection
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    // One byte past the limit tells a line that's too long from one that's exactly MAX_LINE
+    let read = reader.take(MAX_LINE as u64 + 1).read_line(&mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if read > MAX_LINE && !line.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+fn handle(lobby: Arc<Mutex<Lobby>>, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let id = {
+        let mut lobby = lobby.lock().unwrap();
+        lobby.connections += 1;
+        lobby.connections
+    };
+    let mut connection = Connection {
+        id,
+        outbox: Outbox::start(stream)?,
+        token: None,
+    };
+
+    loop {
+        let line = match read_line(&mut reader) {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(reason_this_failed) if reason_this_failed.kind() == io::ErrorKind::InvalidData => {
+                connection.outbox.push(format!("ERR {}", reason_this_failed));
+                break;
+            }
+            Err(_) => break, // The connection dropped
+        };
+        let mut lobby = lobby.lock().unwrap();
+        let reply = match lobby.command(&mut connection, &line) {
+            Ok(reply) => format!("OK {}", reply),
+            Err(reason_this_failed) => format!("ERR {}", reason_this_failed),
+        };
+        let bye = reply == "OK BYE";
+        if !connection.outbox.push(reply) || bye {
+            break;
+        }
+    }
+
+    lobby.lock().unwrap().disconnect(&connection);
+    Ok(())
+}
+
+/// Serves forever, one thread per connection
+pub fn serve(listener: TcpListener) {
+    serve_with(listener, AWAY_TURN);
+}
+
+/// serve() with a different wait before the server plays for someone who's away
+pub fn serve_with(listener: TcpListener, away_turn: Duration) {
+    let lobby = Arc::new(Mutex::new(Lobby::new(away_turn)));
+    let timer = Arc::clone(&lobby);
+    thread::spawn(move || loop {
+        thread::sleep(TIMER_TICK);
+        timer.lock().unwrap().act_for_away();
+    });
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(reason_this_failed) => {
+                eprintln!("accept failed: {}", reason_this_failed);
+                continue;
+            }
+        };
+        let lobby = Arc::clone(&lobby);
+        thread::spawn(move || {
+            if let Err(reason_this_failed) = handle(lobby, stream) {
+                eprintln!("connection failed: {}", reason_this_failed);
+            }
+        });
+    }
+}
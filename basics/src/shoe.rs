@@ -0,0 +1,181 @@
+use super::card::{Card, Rank};
+use super::deck::{shuffle_cards, Deck};
+use super::odds::{self, Odds};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/**
+A casino shoe, several decks shuffled together
+
+- Penetration is how far into the shoe the cut card sits, 0.75 = three quarters of the cards are dealt
+- When the cut card comes out the current round is finished, start_round() then reshuffles
+- Burned cards go straight to the discards without being seen
+- The shoe keeps the Hi-Lo running count of the cards it deals face up. Burned cards aren't seen so
+  they don't count, and reshuffling starts the count again from 0
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShoeError {
+    NoDecks,
+    InvalidPenetration(f64),
+}
+
+impl fmt::Display for ShoeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShoeError::NoDecks => write!(f, "a shoe needs at least one deck"),
+            ShoeError::InvalidPenetration(penetration) => write!(
+                f,
+                "penetration must be between 0 and 1, got {}",
+                penetration
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShoeError {}
+
+#[derive(Debug)]
+pub struct Shoe {
+    cards: Vec<Card>, // Top of the shoe is the end of the vector, same as Deck
+    discards: Vec<Card>,
+    decks: usize,
+    penetration: f64,
+    cut_card: usize, // Cards dealt before the cut card comes out
+    dealt: usize,
+    cut_card_reached: bool,
+    running_count: i32,
+    rng: Option<ChaCha8Rng>,
+}
+
+impl Shoe {
+    pub fn new(num_decks: usize, penetration: f64) -> Result<Self, ShoeError> {
+        Shoe::from_decks((0..num_decks).map(|_| Deck::new()).collect(), penetration, None)
+    }
+
+    /// Same as new() but every shuffle comes from `seed`
+    pub fn with_seed(num_decks: usize, penetration: f64, seed: u64) -> Result<Self, ShoeError> {
+        Shoe::from_decks(
+            (0..num_decks).map(|_| Deck::new()).collect(),
+            penetration,
+            Some(seed),
+        )
+    }
+
+    pub fn from_decks(decks: Vec<Deck>, penetration: f64, seed: Option<u64>) -> Result<Self, ShoeError> {
+        if decks.is_empty() {
+            return Err(ShoeError::NoDecks);
+        }
+        if !(penetration > 0.0 && penetration <= 1.0) {
+            return Err(ShoeError::InvalidPenetration(penetration));
+        }
+
+        let mut shoe = Shoe {
+            decks: decks.len(),
+            cards: decks.into_iter().flat_map(Deck::into_cards).collect(),
+            discards: vec![],
+            penetration,
+            cut_card: 0,
+            dealt: 0,
+            cut_card_reached: false,
+            running_count: 0,
+            rng: seed.map(ChaCha8Rng::seed_from_u64),
+        };
+        shoe.reshuffle();
+        Ok(shoe)
+    }
+
+    /// Gathers every discard back in, shuffles, and places the cut card
+    pub fn reshuffle(&mut self) {
+        self.cards.append(&mut self.discards);
+        shuffle_cards(&mut self.cards, self.rng.as_mut());
+        self.cut_card = (self.cards.len() as f64 * self.penetration).round() as usize;
+        self.dealt = 0;
+        self.cut_card_reached = false;
+        self.running_count = 0;
+    }
+
+    /// Call before each round. Reshuffles if the cut card came out last round, returns whether it did
+    pub fn start_round(&mut self) -> bool {
+        if self.cut_card_reached {
+            self.reshuffle();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn draw(&mut self) -> Option<Card> {
+        let card = self.take()?;
+        self.running_count += odds::hi_lo(card.rank);
+        Some(card)
+    }
+
+    // Off the top without anyone seeing it
+    fn take(&mut self) -> Option<Card> {
+        let card = self.cards.pop()?;
+        self.dealt += 1;
+        if self.dealt >= self.cut_card {
+            self.cut_card_reached = true;
+        }
+        Some(card)
+    }
+
+    /// Takes cards off the top unseen. Returns how many were burned
+    pub fn burn(&mut self, num_cards: usize) -> usize {
+        let mut burned = 0;
+        while burned < num_cards {
+            match self.take() {
+                Some(card) => self.discards.push(card),
+                None => break,
+            }
+            burned += 1;
+        }
+        burned
+    }
+
+    pub fn discard(&mut self, cards: Vec<Card>) {
+        self.discards.extend(cards);
+    }
+
+    pub fn cut_card_reached(&self) -> bool {
+        self.cut_card_reached
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn cards_until_cut(&self) -> usize {
+        self.cut_card.saturating_sub(self.dealt)
+    }
+
+    pub fn decks(&self) -> usize {
+        self.decks
+    }
+
+    /// How many of each rank are still in the shoe, every rank listed even when none are left
+    pub fn remaining_by_rank(&self) -> BTreeMap<Rank, usize> {
+        let mut counts: BTreeMap<Rank, usize> = Rank::ALL.into_iter().map(|rank| (rank, 0)).collect();
+        for card in &self.cards {
+            *counts.entry(card.rank).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Counts and chances for the cards still in the shoe. Burned cards are already gone from these
+    pub fn odds(&self) -> Odds {
+        Odds::new(&self.cards)
+    }
+
+    /// Hi-Lo count of the cards drawn since the last shuffle
+    pub fn running_count(&self) -> i32 {
+        self.running_count
+    }
+
+    /// The running count per deck left in the shoe
+    pub fn true_count(&self) -> f64 {
+        odds::true_count(self.running_count, self.cards.len())
+    }
+}
@@ -0,0 +1,174 @@
+use super::deck::Deck;
+use super::shuffles::Technique;
+use std::fmt;
+
+/**
+Statistical checks that a shuffle is unbiased
+
+Each trial shuffles the same deck again (it isn't reset), comparing where every card was before
+with where it ended up:
+- Positions: a chi-square test on the card position x new position table. Every cell should be
+  trials / deck size, (n-1)^2 degrees of freedom
+- Adjacency: pairs of cards that were next to each other and still are, in the same order.
+  A random deck keeps (n-1)/n of them on average, the count is close to Poisson so the
+  z-score is (observed - expected) / sqrt(expected)
+- Rising sequences: runs of cards still in their old relative order. A random deck has (n+1)/2,
+  one riffle leaves at most 2, Bayer and Diaconis use this to show why seven riffles are needed
+
+The p-value uses the Wilson-Hilferty approximation, fine for the thousands of degrees of freedom here.
+It needs at least 2 cards and 1 trial, with fewer there's nothing to compare
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum QualityError {
+    TooFewCards(usize),
+    NoTrials,
+}
+
+impl fmt::Display for QualityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QualityError::TooFewCards(cards) => write!(f, "need at least 2 cards to shuffle, got {}", cards),
+            QualityError::NoTrials => write!(f, "need at least 1 trial"),
+        }
+    }
+}
+
+impl std::error::Error for QualityError {}
+
+#[derive(Debug, Clone)]
+pub struct QualityReport {
+    pub technique: Technique,
+    pub passes: usize, // Shuffles per trial, e.g. 7 riffles
+    pub trials: u64,
+    pub deck_size: usize,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+    pub adjacent_pairs: f64, // Mean per trial
+    pub adjacency_z: f64,
+    pub rising_sequences: f64, // Mean per trial
+}
+
+impl QualityReport {
+    /// No evidence of bias at significance `alpha` (0.001 is a sensible choice with this many tests)
+    pub fn looks_random(&self, alpha: f64) -> bool {
+        self.p_value >= alpha && normal_upper_tail(self.adjacency_z.abs()) * 2.0 >= alpha
+    }
+}
+
+impl fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:>12} x{:<4} chi2 {:>12.1} (df {}) p {:.4}  adjacent {:.3} (z {:+8.1})  rising {:.2}",
+            self.technique.name(),
+            self.passes,
+            self.chi_square,
+            self.degrees_of_freedom,
+            self.p_value,
+            self.adjacent_pairs,
+            self.adjacency_z,
+            self.rising_sequences
+        )
+    }
+}
+
+/// Whether measure() can run with these settings
+pub fn check(deck_size: usize, trials: u64) -> Result<(), QualityError> {
+    if deck_size < 2 {
+        return Err(QualityError::TooFewCards(deck_size));
+    }
+    if trials == 0 {
+        return Err(QualityError::NoTrials);
+    }
+    Ok(())
+}
+
+/// Runs `trials` trials of `passes` shuffles each on a seeded deck of `deck_size` cards.
+/// Fisher-Yates goes through Deck::shuffle(), the rest through Deck::shuffle_by()
+pub fn measure(
+    technique: Technique,
+    passes: usize,
+    trials: u64,
+    deck_size: usize,
+    seed: u64,
+) -> Result<QualityReport, QualityError> {
+    check(deck_size, trials)?;
+    let n = deck_size;
+    let mut deck = Deck::from_cards((0..n).collect::<Vec<usize>>());
+    deck.shuffle_seeded(seed);
+
+    let mut counts = vec![0u64; n * n]; // Old position x new position
+    let mut adjacent = 0u64;
+    let mut rising = 0u64;
+    let mut old_position = vec![0usize; n]; // By card
+    let mut new_position = vec![0usize; n]; // By old position
+
+    for _ in 0..trials {
+        for (position, card) in deck.cards().iter().enumerate() {
+            old_position[*card] = position;
+        }
+        for _ in 0..passes {
+            match technique {
+                Technique::FisherYates => deck.shuffle(),
+                other => deck.shuffle_by(other),
+            }
+        }
+
+        for (position, card) in deck.cards().iter().enumerate() {
+            let old = old_position[*card];
+            counts[old * n + position] += 1;
+            new_position[old] = position;
+        }
+        for old in 1..n {
+            if new_position[old] == new_position[old - 1] + 1 {
+                adjacent += 1;
+            }
+            if new_position[old] < new_position[old - 1] {
+                rising += 1;
+            }
+        }
+        rising += 1;
+    }
+
+    let expected = trials as f64 / n as f64;
+    let chi_square: f64 = counts
+        .iter()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum();
+    let degrees_of_freedom = (n - 1) * (n - 1);
+    let expected_adjacent = trials as f64 * (n - 1) as f64 / n as f64;
+
+    Ok(QualityReport {
+        technique,
+        passes,
+        trials,
+        deck_size: n,
+        chi_square,
+        degrees_of_freedom,
+        p_value: chi_square_upper_tail(chi_square, degrees_of_freedom),
+        adjacent_pairs: adjacent as f64 / trials as f64,
+        adjacency_z: (adjacent as f64 - expected_adjacent) / expected_adjacent.sqrt(),
+        rising_sequences: rising as f64 / trials as f64,
+    })
+}
+
+/// P(X >= statistic) for a chi-square with `df` degrees of freedom (Wilson-Hilferty)
+pub fn chi_square_upper_tail(statistic: f64, df: usize) -> f64 {
+    let k = df as f64;
+    let z = ((statistic / k).cbrt() - (1.0 - 2.0 / (9.0 * k))) / (2.0 / (9.0 * k)).sqrt();
+    normal_upper_tail(z)
+}
+
+/// P(Z >= z) for a standard normal, Abramowitz and Stegun 7.1.26 (error below 1.5e-7)
+pub fn normal_upper_tail(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erfc = poly * (-x * x).exp();
+    if z >= 0.0 {
+        erfc / 2.0
+    } else {
+        1.0 - erfc / 2.0
+    }
+}
@@ -0,0 +1,93 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/**
+Shuffles people actually do, for simulations where a perfectly random deck is wrong
+
+- Riffle: the Gilbert-Shannon-Reeds model. Cut about in half (binomially), then drop cards from
+  each half with chance proportional to how many are left in it. Seven of these is close to random
+- Overhand: small packets slid off the top one after another, so each packet lands in reverse order
+  while the cards inside it stay together. Thousands are needed to properly randomise a deck,
+  though simple tests like shuffle_quality's stop noticing after about a hundred
+- Cut: one cut near the middle, the top part goes under. Never randomises anything on its own
+- FisherYates is what Deck::shuffle() does, a perfectly uniform shuffle to compare against
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Technique {
+    FisherYates,
+    Riffle,
+    Overhand,
+    Cut,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 4] = [
+        Technique::FisherYates,
+        Technique::Riffle,
+        Technique::Overhand,
+        Technique::Cut,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::FisherYates => "Fisher-Yates",
+            Technique::Riffle => "riffle",
+            Technique::Overhand => "overhand",
+            Technique::Cut => "cut",
+        }
+    }
+
+    pub fn apply<C, R: Rng + ?Sized>(&self, cards: &mut Vec<C>, rng: &mut R) {
+        match self {
+            Technique::FisherYates => cards.shuffle(rng),
+            Technique::Riffle => riffle(cards, rng),
+            Technique::Overhand => overhand(cards, rng),
+            Technique::Cut => cut(cards, rng),
+        }
+    }
+}
+
+// Heads out of n fair coin flips, how a person's "half" comes out
+fn binomial_half<R: Rng + ?Sized>(n: usize, rng: &mut R) -> usize {
+    (0..n).filter(|_| rng.gen_bool(0.5)).count()
+}
+
+pub fn riffle<C, R: Rng + ?Sized>(cards: &mut Vec<C>, rng: &mut R) {
+    let split = binomial_half(cards.len(), rng);
+    let mut right = cards.split_off(split);
+    let mut left = std::mem::take(cards);
+
+    // Build the new deck from the bottom, so drop from the bottom of each half
+    left.reverse();
+    right.reverse();
+    while !left.is_empty() || !right.is_empty() {
+        let from_left = rng.gen_range(0..left.len() + right.len()) < left.len();
+        let card = if from_left { left.pop() } else { right.pop() };
+        cards.extend(card);
+    }
+}
+
+/// Each gap between two cards is a packet boundary with chance 1 in 8, packets of about 8 cards
+pub fn overhand<C, R: Rng + ?Sized>(cards: &mut Vec<C>, rng: &mut R) {
+    let mut packets: Vec<Vec<C>> = vec![];
+    // Off the top (the end of the vector) one packet at a time
+    while !cards.is_empty() {
+        let mut size = 1;
+        while size < cards.len() && !rng.gen_bool(1.0 / 8.0) {
+            size += 1;
+        }
+        packets.push(cards.split_off(cards.len() - size));
+    }
+    // The first packet off the top ends up on the bottom of the new pile
+    for packet in packets {
+        cards.extend(packet);
+    }
+}
+
+pub fn cut<C, R: Rng + ?Sized>(cards: &mut [C], rng: &mut R) {
+    if cards.len() < 2 {
+        return;
+    }
+    let at = binomial_half(cards.len(), rng).clamp(1, cards.len() - 1);
+    cards.rotate_left(at);
+}
@@ -0,0 +1,274 @@
+use super::blackjack::{card_value, Game, Hand, PlayerAction, Rules};
+use super::card::{Card, Rank};
+use super::deck::Deck;
+use std::fmt;
+use std::thread;
+
+/**
+Monte Carlo simulation
+
+- run() plays hands split across threads. Each thread builds its own trial from its own seed,
+  so nothing is shared while playing and a (seed, threads) pair always gives the same report
+- Thread seeds are spread out with SplitMix64 so neighbouring seeds don't give related shuffles
+- Results are in bets: +1.0 is winning the amount bet, -0.5 a surrender, +1.5 a blackjack
+- The confidence interval is the usual normal approximation, mean +/- 1.96 standard errors
+*/
+pub trait Trial {
+    /// Plays one hand and returns the result in bets won (negative for lost)
+    fn play(&mut self) -> f64;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub hands: u64,
+    pub wins: u64,
+    pub pushes: u64,
+    pub losses: u64,
+    pub total: f64,
+    sum_of_squares: f64,
+}
+
+impl Report {
+    fn record(&mut self, result: f64) {
+        self.hands += 1;
+        self.total += result;
+        self.sum_of_squares += result * result;
+        if result > 0.0 {
+            self.wins += 1;
+        } else if result < 0.0 {
+            self.losses += 1;
+        } else {
+            self.pushes += 1;
+        }
+    }
+
+    fn merge(&mut self, other: Report) {
+        self.hands += other.hands;
+        self.wins += other.wins;
+        self.pushes += other.pushes;
+        self.losses += other.losses;
+        self.total += other.total;
+        self.sum_of_squares += other.sum_of_squares;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.hands.max(1) as f64
+    }
+
+    /// Expected value per hand, in bets
+    pub fn ev(&self) -> f64 {
+        self.total / self.hands.max(1) as f64
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        if self.hands < 2 {
+            return 0.0;
+        }
+        let n = self.hands as f64;
+        ((self.sum_of_squares - self.total * self.total / n) / (n - 1.0)).max(0.0).sqrt()
+    }
+
+    /// 95% confidence interval for the EV
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.std_dev() / (self.hands.max(1) as f64).sqrt();
+        (self.ev() - margin, self.ev() + margin)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (low, high) = self.confidence_interval();
+        write!(
+            f,
+            "{} hands: win {:.2}% push {:.2}% lose {:.2}%, EV {:+.4} bets/hand (95% CI {:+.4} to {:+.4})",
+            self.hands,
+            100.0 * self.win_rate(),
+            100.0 * self.pushes as f64 / self.hands.max(1) as f64,
+            100.0 * self.losses as f64 / self.hands.max(1) as f64,
+            self.ev(),
+            low,
+            high
+        )
+    }
+}
+
+pub(crate) fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Plays `hands` hands over `threads` threads. `new_trial` gets each thread's seed
+pub fn run<T, F>(hands: u64, threads: usize, seed: u64, new_trial: F) -> Report
+where
+    T: Trial,
+    F: Fn(u64) -> T + Sync,
+{
+    let threads = threads.max(1) as u64;
+    let new_trial = &new_trial;
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|index| {
+                // First threads take the remainder so every hand is played
+                let share = hands / threads + u64::from(index < hands % threads);
+                scope.spawn(move || {
+                    let mut trial = new_trial(splitmix64(seed ^ index));
+                    let mut report = Report::default();
+                    for _ in 0..share {
+                        report.record(trial.play());
+                    }
+                    report
+                })
+            })
+            .collect();
+
+        let mut report = Report::default();
+        for worker in workers {
+            report.merge(worker.join().expect("simulation thread panicked"));
+        }
+        report
+    })
+}
+
+/// How a Blackjack player makes decisions
+pub trait Strategy {
+    fn take_insurance(&self, _hand: &Hand, _upcard: Card) -> bool {
+        false
+    }
+
+    /// `available` is never empty and always contains Hit and Stand unless the hand is on 21
+    fn decide(&self, hand: &Hand, upcard: Card, available: &[PlayerAction]) -> PlayerAction;
+}
+
+/// Plays like the dealer: hit below 17, nothing else
+pub struct MimicDealer;
+
+impl Strategy for MimicDealer {
+    fn decide(&self, hand: &Hand, _upcard: Card, available: &[PlayerAction]) -> PlayerAction {
+        if hand.value().0 < 17 && available.contains(&PlayerAction::Hit) {
+            PlayerAction::Hit
+        } else {
+            PlayerAction::Stand
+        }
+    }
+}
+
+/// The standard multi-deck basic strategy chart (dealer stands on soft 17, double after split, late surrender)
+pub struct BasicStrategy;
+
+impl Strategy for BasicStrategy {
+    fn decide(&self, hand: &Hand, upcard: Card, available: &[PlayerAction]) -> PlayerAction {
+        use PlayerAction::*;
+        let can = |action| available.contains(&action);
+        let dealer = match upcard.rank {
+            Rank::Ace => 11,
+            _ => card_value(&upcard),
+        };
+        let (total, soft) = hand.value();
+
+        if can(Split) {
+            let split = match card_value(&hand.cards[0]) {
+                1 | 8 => true,
+                9 => !matches!(dealer, 7 | 10 | 11),
+                7 | 2 | 3 => dealer <= 7,
+                6 => dealer <= 6,
+                4 => dealer == 5 || dealer == 6,
+                _ => false, // Tens and fives play as a total
+            };
+            if split {
+                return Split;
+            }
+        }
+
+        if can(Surrender) && !soft && ((total == 16 && dealer >= 9) || (total == 15 && dealer == 10)) {
+            return Surrender;
+        }
+
+        let double_or = |otherwise| if can(Double) { Double } else { otherwise };
+        let choice = if soft {
+            match total {
+                13 | 14 if dealer == 5 || dealer == 6 => double_or(Hit),
+                15 | 16 if (4..=6).contains(&dealer) => double_or(Hit),
+                17 if (3..=6).contains(&dealer) => double_or(Hit),
+                18 if (3..=6).contains(&dealer) => double_or(Stand),
+                18 if dealer >= 9 => Hit,
+                18.. => Stand,
+                _ => Hit,
+            }
+        } else {
+            match total {
+                9 if (3..=6).contains(&dealer) => double_or(Hit),
+                10 if dealer <= 9 => double_or(Hit),
+                11 if dealer <= 10 => double_or(Hit),
+                12 if (4..=6).contains(&dealer) => Stand,
+                13..=16 if dealer <= 6 => Stand,
+                17.. => Stand,
+                _ => Hit,
+            }
+        };
+
+        if can(choice) {
+            choice
+        } else {
+            Stand
+        }
+    }
+}
+
+/// One player at a Blackjack table following a strategy.
+/// Bets 2 chips a hand so a surrender's half bet is still a whole chip
+pub struct BlackjackTrial<'a, S: Strategy> {
+    game: Game,
+    strategy: &'a S,
+}
+
+const BET: i64 = 2;
+
+impl<'a, S: Strategy> BlackjackTrial<'a, S> {
+    pub fn new(strategy: &'a S, rules: Rules, seed: u64) -> Self {
+        let mut deck = Deck::with_seed(seed);
+        deck.shuffle();
+        BlackjackTrial {
+            game: Game::new(deck, rules, i64::MAX / 2),
+            strategy,
+        }
+    }
+}
+
+impl<S: Strategy> Trial for BlackjackTrial<'_, S> {
+    fn play(&mut self) -> f64 {
+        let game = &mut self.game;
+        let mut result = game.start_round(BET).expect("bankroll covers every bet");
+
+        if result.is_none() && game.insurance_offered() {
+            let take = self
+                .strategy
+                .take_insurance(&game.hands()[0], game.dealer_upcard().unwrap());
+            result = game.insurance(take).expect("insurance was offered");
+        }
+
+        while result.is_none() {
+            let upcard = game.dealer_upcard().unwrap();
+            let (_, hand) = game.current_hand().unwrap();
+            let action = self.strategy.decide(hand, upcard, &game.available_actions());
+            result = game.act(action).expect("strategy picked an available action");
+        }
+
+        result.unwrap().net as f64 / BET as f64
+    }
+}
+
+/// Plays `hands` hands of Blackjack with `strategy`
+pub fn simulate_blackjack<S: Strategy + Sync>(
+    strategy: &S,
+    rules: &Rules,
+    hands: u64,
+    threads: usize,
+    seed: u64,
+) -> Report {
+    run(hands, threads, seed, |thread_seed| {
+        BlackjackTrial::new(strategy, rules.clone(), thread_seed)
+    })
+}
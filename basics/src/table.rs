@@ -0,0 +1,633 @@
+use super::card::Card;
+use super::deck::{DealError, Deck};
+use super::hand_history::{Event, HandHistory, SeatEntry};
+use std::fmt;
+
+/**
+A table of players sharing one deck
+
+- Seats are numbered from 0 and keep their number while other players come and go
+- The button marks the dealer. Dealing and turns go round the table starting left of the button
+- A hand is Waiting -> InHand -> Waiting. The game (a CardGame) deals, takes bets and decides
+  winners, the table checks whose turn it is, keeps the chips and returns cards to the deck
+- Chips a player puts in during a hand are tracked so a hand that can't start is refunded, and so
+  a player all in for less can only win that much from each other player. The rest goes in side
+  pots the others contest. A pot nobody wins goes back to whoever put chips in it
+- Anyone who sits down during a hand sits out until the next one. Only players sitting the hand
+  out can leave mid-hand. Someone who folded waits for the hand to end, the chips they put in
+  are still in the pot and settling it reads them from their seat
+- The table writes down each hand as it goes: antes, bets, deals, draws, folds, the showdown and
+  who collected what. Whatever the game, last_hand() has the finished hand for a hand history.
+  Games only mark the streets themselves, the table can't know where one ends
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableState {
+    Waiting,
+    InHand,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    TableFull,
+    NoSuchSeat(usize),
+    EmptySeat(usize),
+    NotEnoughPlayers { needed: usize, seated: usize },
+    TooManyPlayers { allowed: usize, seated: usize },
+    HandInProgress,
+    NoHandInProgress,
+    NotYourTurn { seat: usize, turn: Option<usize> },
+    NotInHand(usize),
+    InsufficientChips { seat: usize, needed: i64, chips: i64 },
+    IllegalAction(String),
+    Deal(DealError),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableError::TableFull => write!(f, "every seat is taken"),
+            TableError::NoSuchSeat(seat) => write!(f, "there is no seat {}", seat),
+            TableError::EmptySeat(seat) => write!(f, "nobody is sitting in seat {}", seat),
+            TableError::NotEnoughPlayers { needed, seated } => {
+                write!(f, "need {} players with chips, only {} seated", needed, seated)
+            }
+            TableError::TooManyPlayers { allowed, seated } => {
+                write!(f, "at most {} players can play, {} seated", allowed, seated)
+            }
+            TableError::HandInProgress => write!(f, "wait for the current hand to finish"),
+            TableError::NoHandInProgress => write!(f, "no hand in progress"),
+            TableError::NotYourTurn { seat, turn: Some(turn) } => {
+                write!(f, "it's seat {}'s turn, not seat {}", turn, seat)
+            }
+            TableError::NotYourTurn { seat, turn: None } => write!(f, "it's nobody's turn, not seat {}", seat),
+            TableError::NotInHand(seat) => write!(f, "seat {} isn't in this hand", seat),
+            TableError::InsufficientChips { seat, needed, chips } => {
+                write!(f, "seat {} needs {} chips but only has {}", seat, needed, chips)
+            }
+            TableError::IllegalAction(reason) => write!(f, "{}", reason),
+            TableError::Deal(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<DealError> for TableError {
+    fn from(err: DealError) -> Self {
+        TableError::Deal(err)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Player<C = Card> {
+    pub name: String,
+    pub chips: i64,
+    pub hand: Vec<C>,
+    pub in_hand: bool,  // False when folded or sitting out
+    pub committed: i64, // Chips put in the pot this hand
+}
+
+/// What the game wants after a player acts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    NextPlayer,
+    SamePlayer,
+    HandOver,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandResult<C = Card> {
+    pub winners: Vec<usize>, // Everyone who won a share of any pot
+    pub pot: i64,
+    pub pots: Vec<(i64, Vec<usize>)>, // Main pot then side pots, each with the seats that shared it
+    pub shown: Vec<(usize, Vec<C>)>, // Hands still in at a showdown, empty when everyone else folded
+}
+
+/// Everything the table saw happen in one hand
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandRecord<C = Card> {
+    pub game: String,
+    pub max_seats: usize,
+    pub button: usize,                     // Seat numbers from 0, like the table
+    pub seats: Vec<(usize, String, i64)>, // Seat, name and chips for everyone dealt in, before antes
+    pub events: Vec<Event<C>>,
+}
+
+impl HandRecord {
+    /// As a PokerStars hand history, `time` in seconds since 1970. Seats are numbered from 1 there
+    pub fn to_history(&self, id: u64, table: &str, time: u64) -> HandHistory {
+        HandHistory {
+            id,
+            game: self.game.clone(),
+            time,
+            table: table.to_string(),
+            max_seats: self.max_seats,
+            button: self.button + 1,
+            seats: self
+                .seats
+                .iter()
+                .map(|(seat, name, chips)| SeatEntry {
+                    seat: seat + 1,
+                    name: name.clone(),
+                    chips: *chips,
+                })
+                .collect(),
+            events: self.events.clone(),
+        }
+    }
+}
+
+/// A game played at a Table. The table calls these, players never call them directly
+pub trait CardGame<C = Card> {
+    type Action;
+
+    /// The game as a hand history names it, e.g. "5 Card Draw (ante 5)"
+    fn name(&self) -> String {
+        String::from("Card game")
+    }
+
+    fn min_players(&self) -> usize {
+        2
+    }
+
+    fn max_players(&self) -> usize {
+        usize::MAX
+    }
+
+    /// The deck is shuffled and the turn is left of the button. Deal and take antes or blinds here
+    fn start_hand(&mut self, table: &mut Table<C>) -> Result<(), TableError>;
+
+    /// `seat` is the player whose turn it is
+    fn act(&mut self, table: &mut Table<C>, seat: usize, action: Self::Action) -> Result<Progress, TableError>;
+
+    /// Seats out of `contenders` that share a pot. Asked once for each pot more than one player can win
+    fn winners(&self, table: &Table<C>, contenders: &[usize]) -> Vec<usize>;
+}
+
+#[derive(Debug)]
+pub struct Table<C = Card> {
+    seats: Vec<Option<Player<C>>>,
+    deck: Deck<C>,
+    state: TableState,
+    button: usize,
+    turn: Option<usize>,
+    pot: i64,
+    record: Option<HandRecord<C>>, // The hand being played
+    last_hand: Option<HandRecord<C>>,
+}
+
+impl<C: Clone> Table<C> {
+    pub fn new(deck: Deck<C>, seats: usize) -> Self {
+        Table {
+            seats: (0..seats).map(|_| None).collect(),
+            deck,
+            state: TableState::Waiting,
+            button: 0,
+            turn: None,
+            pot: 0,
+            record: None,
+            last_hand: None,
+        }
+    }
+
+    /// Takes the first empty seat
+    pub fn sit(&mut self, name: &str, chips: i64) -> Result<usize, TableError> {
+        let seat = self.seats.iter().position(Option::is_none).ok_or(TableError::TableFull)?;
+        self.seats[seat] = Some(Player {
+            name: name.to_string(),
+            chips,
+            hand: vec![],
+            in_hand: false,
+            committed: 0,
+        });
+        Ok(seat)
+    }
+
+    pub fn leave(&mut self, seat: usize) -> Result<Player<C>, TableError> {
+        let player = self.player(seat)?;
+        if (player.in_hand || player.committed > 0) && self.state == TableState::InHand {
+            return Err(TableError::HandInProgress);
+        }
+        let mut player = self.seats[seat].take().unwrap();
+        self.deck.discard(std::mem::take(&mut player.hand));
+        Ok(player)
+    }
+
+    pub fn player(&self, seat: usize) -> Result<&Player<C>, TableError> {
+        self.seats
+            .get(seat)
+            .ok_or(TableError::NoSuchSeat(seat))?
+            .as_ref()
+            .ok_or(TableError::EmptySeat(seat))
+    }
+
+    pub fn player_mut(&mut self, seat: usize) -> Result<&mut Player<C>, TableError> {
+        self.seats
+            .get_mut(seat)
+            .ok_or(TableError::NoSuchSeat(seat))?
+            .as_mut()
+            .ok_or(TableError::EmptySeat(seat))
+    }
+
+    /// Occupied seats and their players, in seat order
+    pub fn players(&self) -> impl Iterator<Item = (usize, &Player<C>)> {
+        self.seats
+            .iter()
+            .enumerate()
+            .filter_map(|(seat, player)| player.as_ref().map(|player| (seat, player)))
+    }
+
+    pub fn seat_count(&self) -> usize {
+        self.seats.len()
+    }
+
+    pub fn state(&self) -> TableState {
+        self.state
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn turn(&self) -> Option<usize> {
+        self.turn
+    }
+
+    pub fn pot(&self) -> i64 {
+        self.pot
+    }
+
+    pub fn deck(&self) -> &Deck<C> {
+        &self.deck
+    }
+
+    /// The last hand that was played to the end
+    pub fn last_hand(&self) -> Option<&HandRecord<C>> {
+        self.last_hand.as_ref()
+    }
+
+    fn record(&mut self, event: Event<C>) {
+        if let Some(record) = self.record.as_mut() {
+            record.events.push(event);
+        }
+    }
+
+    fn name_of(&self, seat: usize) -> String {
+        self.player(seat).map(|player| player.name.clone()).unwrap_or_default()
+    }
+
+    /// Starts a new street in the hand's record, with any board cards it deals
+    pub fn street(&mut self, name: &str, cards: Vec<C>) {
+        self.record(Event::Street {
+            name: name.to_string(),
+            cards,
+        });
+    }
+
+    /// Seats still in the hand, starting left of the button
+    pub fn in_hand(&self) -> Vec<usize> {
+        let count = self.seats.len();
+        (1..=count)
+            .map(|offset| (self.button + offset) % count)
+            .filter(|seat| matches!(&self.seats[*seat], Some(player) if player.in_hand))
+            .collect()
+    }
+
+    /// Deals `cards_each` to everyone in the hand, one card at a time
+    pub fn deal_round_robin(&mut self, cards_each: usize) -> Result<(), TableError> {
+        let order = self.in_hand();
+        // Check first so a short deck doesn't leave some players with fewer cards
+        if order.len() * cards_each > self.deck.remaining() {
+            return Err(DealError::NotEnoughCards {
+                requested: order.len() * cards_each,
+                remaining: self.deck.remaining(),
+            }
+            .into());
+        }
+        for _ in 0..cards_each {
+            for seat in &order {
+                let card = self.deck.draw_one().unwrap();
+                self.player_mut(*seat)?.hand.push(card);
+            }
+        }
+        for seat in order {
+            let cards = self.player(seat)?.hand.clone();
+            let player = self.name_of(seat);
+            self.record(Event::Dealt { player, cards });
+        }
+        Ok(())
+    }
+
+    /// Cards straight after discard_from() are recorded as that player's draw
+    pub fn deal_to(&mut self, seat: usize, num_cards: usize) -> Result<(), TableError> {
+        let player = self.player(seat)?.name.clone();
+        let cards = self.deck.deal(num_cards)?;
+        self.player_mut(seat)?.hand.extend(cards.iter().cloned());
+        match self.record.as_mut().and_then(|record| record.events.last_mut()) {
+            Some(Event::Draw { player: drawing, drawn, .. }) if *drawing == player && drawn.is_empty() => *drawn = cards,
+            _ => self.record(Event::Dealt { player, cards }),
+        }
+        Ok(())
+    }
+
+    /// Moves the cards at `indices` from the player's hand to the discard pile
+    pub fn discard_from(&mut self, seat: usize, indices: &[usize]) -> Result<(), TableError> {
+        let hand = &mut self.player_mut(seat)?.hand;
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.last().is_some_and(|index| *index >= hand.len()) {
+            return Err(TableError::IllegalAction(format!("only {} cards in hand", hand.len())));
+        }
+        let mut discarded: Vec<C> = indices.iter().rev().map(|index| hand.remove(*index)).collect();
+        discarded.reverse(); // Back in hand order
+        self.record(Event::Draw {
+            player: self.name_of(seat),
+            discarded: discarded.clone(),
+            drawn: vec![],
+        });
+        self.deck.discard(discarded);
+        Ok(())
+    }
+
+    /// Moves chips from the player to the pot
+    pub fn bet(&mut self, seat: usize, amount: i64) -> Result<(), TableError> {
+        self.put_in(seat, amount)?;
+        self.record(Event::Bet {
+            player: self.name_of(seat),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// A bet recorded as an ante
+    pub fn ante(&mut self, seat: usize, amount: i64) -> Result<(), TableError> {
+        self.put_in(seat, amount)?;
+        self.record(Event::Ante {
+            player: self.name_of(seat),
+            amount,
+        });
+        Ok(())
+    }
+
+    fn put_in(&mut self, seat: usize, amount: i64) -> Result<(), TableError> {
+        let player = self.player_mut(seat)?;
+        if amount < 0 || amount > player.chips {
+            return Err(TableError::InsufficientChips {
+                seat,
+                needed: amount,
+                chips: player.chips,
+            });
+        }
+        player.chips -= amount;
+        player.committed += amount;
+        self.pot += amount;
+        Ok(())
+    }
+
+    /// Out of the hand, cards to the discard pile
+    pub fn fold(&mut self, seat: usize) -> Result<(), TableError> {
+        let player = self.player_mut(seat)?;
+        if !player.in_hand {
+            return Err(TableError::NotInHand(seat));
+        }
+        player.in_hand = false;
+        let cards = std::mem::take(&mut player.hand);
+        self.deck.discard(cards);
+        self.record(Event::Fold {
+            player: self.name_of(seat),
+        });
+        Ok(())
+    }
+
+    pub fn start_hand<G: CardGame<C>>(&mut self, game: &mut G) -> Result<(), TableError> {
+        if self.state == TableState::InHand {
+            return Err(TableError::HandInProgress);
+        }
+        let playing: Vec<usize> = self
+            .players()
+            .filter(|(_, player)| player.chips > 0)
+            .map(|(seat, _)| seat)
+            .collect();
+        if playing.len() < game.min_players() {
+            return Err(TableError::NotEnoughPlayers {
+                needed: game.min_players(),
+                seated: playing.len(),
+            });
+        }
+        if playing.len() > game.max_players() {
+            return Err(TableError::TooManyPlayers {
+                allowed: game.max_players(),
+                seated: playing.len(),
+            });
+        }
+
+        self.record = Some(HandRecord {
+            game: game.name(),
+            max_seats: self.seats.len(),
+            button: self.button,
+            seats: playing
+                .iter()
+                .map(|seat| (*seat, self.name_of(*seat), self.player(*seat).map_or(0, |player| player.chips)))
+                .collect(),
+            events: vec![],
+        });
+        for seat in playing {
+            self.player_mut(seat)?.in_hand = true;
+        }
+        self.deck.reshuffle_discards();
+        self.deck.shuffle();
+        self.state = TableState::InHand;
+        self.turn = self.in_hand().first().copied();
+
+        if let Err(reason_this_failed) = game.start_hand(self) {
+            // Give back anything the game took before it failed
+            for seat in 0..self.seats.len() {
+                if let Ok(player) = self.player_mut(seat) {
+                    player.chips += player.committed;
+                }
+            }
+            self.pot = 0;
+            self.record = None;
+            self.end_hand();
+            return Err(reason_this_failed);
+        }
+        Ok(())
+    }
+
+    /// The player whose turn it is acts. Returns the result once the hand is over
+    pub fn act<G: CardGame<C>>(
+        &mut self,
+        game: &mut G,
+        seat: usize,
+        action: G::Action,
+    ) -> Result<Option<HandResult<C>>, TableError> {
+        if self.state != TableState::InHand {
+            return Err(TableError::NoHandInProgress);
+        }
+        if self.turn != Some(seat) {
+            return Err(TableError::NotYourTurn { seat, turn: self.turn });
+        }
+
+        let progress = game.act(self, seat, action)?;
+        let remaining = self.in_hand();
+        if remaining.len() <= 1 {
+            return Ok(Some(self.settle(|_, contenders| contenders.to_vec(), vec![])));
+        }
+        match progress {
+            Progress::HandOver => {
+                let shown = remaining
+                    .iter()
+                    .map(|seat| (*seat, self.seats[*seat].as_ref().unwrap().hand.clone()))
+                    .collect();
+                Ok(Some(self.settle(|table, contenders| game.winners(table, contenders), shown)))
+            }
+            Progress::NextPlayer => {
+                let count = self.seats.len();
+                self.turn = (1..=count)
+                    .map(|offset| (seat + offset) % count)
+                    .find(|next| remaining.contains(next));
+                Ok(None)
+            }
+            Progress::SamePlayer => Ok(None),
+        }
+    }
+
+    // Layers of the pot as (from, to, contenders). Every player's chips between from and to go in that
+    // layer, and only seats still in that put in at least `to` can win it. The top layer runs to
+    // i64::MAX so chips from anyone who folded after putting in more than the rest still get won
+    fn pot_layers(&self) -> Vec<(i64, i64, Vec<usize>)> {
+        let in_hand = self.in_hand();
+        let committed = |seat: &usize| self.seats[*seat].as_ref().map_or(0, |player| player.committed);
+        let mut levels: Vec<i64> = in_hand.iter().map(committed).collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut from = 0;
+        let mut layers: Vec<(i64, i64, Vec<usize>)> = levels
+            .into_iter()
+            .map(|to| {
+                let contenders = in_hand.iter().filter(|seat| committed(seat) >= to).copied().collect();
+                let layer = (from, to, contenders);
+                from = to;
+                layer
+            })
+            .collect();
+        match layers.last_mut() {
+            Some(top) => top.1 = i64::MAX,
+            None => layers.push((0, i64::MAX, vec![])),
+        }
+        layers
+    }
+
+    // Splits each pot between its winners, odd chips to the first winners left of the button
+    fn settle<W>(&mut self, winners_of: W, shown: Vec<(usize, Vec<C>)>) -> HandResult<C>
+    where
+        W: Fn(&Table<C>, &[usize]) -> Vec<usize>,
+    {
+        let order = self.in_hand();
+        let committed: Vec<i64> = self
+            .seats
+            .iter()
+            .map(|player| player.as_ref().map_or(0, |player| player.committed))
+            .collect();
+        if !shown.is_empty() {
+            self.street("SHOW DOWN", vec![]);
+            for (seat, cards) in &shown {
+                let player = self.name_of(*seat);
+                self.record(Event::Shows {
+                    player,
+                    cards: cards.clone(),
+                });
+            }
+        }
+        let mut all_winners: Vec<usize> = vec![];
+        let mut pots = vec![];
+        let mut won = vec![0; self.seats.len()];
+        let mut returned = vec![0; self.seats.len()];
+        for (from, to, contenders) in self.pot_layers() {
+            let shares: Vec<i64> = committed.iter().map(|chips| chips.min(&to) - chips.min(&from)).collect();
+            let pot: i64 = shares.iter().sum();
+            if pot == 0 {
+                continue;
+            }
+            let mut winners = match contenders.len() {
+                0 | 1 => contenders.clone(),
+                _ => winners_of(self, &contenders),
+            };
+            winners.retain(|seat| contenders.contains(seat));
+            winners.sort_by_key(|seat| order.iter().position(|other| other == seat));
+            winners.dedup();
+
+            if winners.is_empty() {
+                // Nobody won it, everyone takes back what they put in
+                for (seat, share) in shares.iter().enumerate() {
+                    if let Ok(player) = self.player_mut(seat) {
+                        player.chips += share;
+                        returned[seat] += share;
+                    }
+                }
+            } else {
+                let share = pot / winners.len() as i64;
+                let odd = (pot % winners.len() as i64) as usize;
+                for (place, seat) in winners.iter().enumerate() {
+                    if let Ok(player) = self.player_mut(*seat) {
+                        player.chips += share + i64::from(place < odd);
+                        won[*seat] += share + i64::from(place < odd);
+                    }
+                }
+            }
+            for seat in &winners {
+                if !all_winners.contains(seat) {
+                    all_winners.push(*seat);
+                }
+            }
+            pots.push((pot, winners));
+        }
+        for (seat, amount) in returned.into_iter().enumerate().filter(|(_, amount)| *amount > 0) {
+            let player = self.name_of(seat);
+            self.record(Event::Returned { player, amount });
+        }
+        for seat in &all_winners {
+            let player = self.name_of(*seat);
+            self.record(Event::Collected {
+                player,
+                amount: won[*seat],
+            });
+        }
+
+        let pot = self.pot;
+        self.pot = 0;
+        self.end_hand();
+        HandResult {
+            winners: all_winners,
+            pot,
+            pots,
+            shown,
+        }
+    }
+
+    fn end_hand(&mut self) {
+        if let Some(record) = self.record.take() {
+            self.last_hand = Some(record);
+        }
+        for seat in 0..self.seats.len() {
+            if let Some(player) = self.seats[seat].as_mut() {
+                player.in_hand = false;
+                player.committed = 0;
+                let cards = std::mem::take(&mut player.hand);
+                self.deck.discard(cards);
+            }
+        }
+        self.state = TableState::Waiting;
+        self.turn = None;
+        // Button moves to the next occupied seat
+        let count = self.seats.len();
+        if let Some(next) = (1..=count)
+            .map(|offset| (self.button + offset) % count)
+            .find(|seat| self.seats[*seat].is_some())
+        {
+            self.button = next;
+        }
+    }
+}
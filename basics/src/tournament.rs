@@ -0,0 +1,436 @@
+use super::simulation::splitmix64;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/**
+Tournaments between bots, for any game that can play one seeded match between two entrants
+
+- Entrants are numbered in entry order, which is also their seeding (0 is the top seed)
+- The caller supplies the match: play(first, second, seed) -> MatchPlay. Every match gets its own
+  seed from the tournament seed, so one match can be replayed on its own from the report
+- Round robin: everyone plays everyone once (the circle method, one round per rotation). With an odd
+  number of entrants one sits out each round
+- Swiss: a fixed number of rounds, each round pairs players on equal points who haven't met yet.
+  An odd player out gets a bye, worth a win
+- Elimination: single knockout on a standard bracket (1 meets 2 only in the final), top seeds get
+  the byes. A drawn match is played again on a new seed, after three draws the higher seed goes through
+- Elo: everyone starts at 1500, K = 32, updated after every match in the order played.
+  The leaderboard ranks by points (win 1, draw 1/2) then Elo
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    RoundRobin,
+    Swiss { rounds: Option<usize> }, // None: enough rounds to separate everyone, log2 of the entrants
+    Elimination,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::RoundRobin => write!(f, "round-robin"),
+            Format::Swiss { rounds: None } => write!(f, "swiss"),
+            Format::Swiss { rounds: Some(rounds) } => write!(f, "swiss:{}", rounds),
+            Format::Elimination => write!(f, "elimination"),
+        }
+    }
+}
+
+/// "round-robin", "swiss", "swiss:5" or "elimination"
+impl FromStr for Format {
+    type Err = TournamentError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "round-robin" | "roundrobin" => Ok(Format::RoundRobin),
+            "swiss" => Ok(Format::Swiss { rounds: None }),
+            "elimination" | "knockout" => Ok(Format::Elimination),
+            other => match other.strip_prefix("swiss:").map(str::parse) {
+                Some(Ok(rounds)) if rounds > 0 => Ok(Format::Swiss { rounds: Some(rounds) }),
+                _ => Err(TournamentError::UnknownFormat(text.to_string())),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TournamentError {
+    NotEnoughEntrants(usize),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TournamentError::NotEnoughEntrants(count) => {
+                write!(f, "a tournament needs at least 2 entrants, got {}", count)
+            }
+            TournamentError::UnknownFormat(text) => write!(
+                f,
+                "'{}' isn't a format, try round-robin, swiss, swiss:ROUNDS or elimination",
+                text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    FirstWins,
+    SecondWins,
+    Draw,
+}
+
+/// What the game reports back for one match
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchPlay {
+    pub outcome: Outcome,
+    pub summary: String, // e.g. the final chip counts
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchRecord {
+    pub round: usize,
+    pub first: usize,
+    pub second: usize,
+    pub seed: u64,
+    pub outcome: Outcome,
+    pub summary: String,
+}
+
+impl MatchRecord {
+    pub fn winner(&self) -> Option<usize> {
+        match self.outcome {
+            Outcome::FirstWins => Some(self.first),
+            Outcome::SecondWins => Some(self.second),
+            Outcome::Draw => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub entrant: usize,
+    pub name: String,
+    pub played: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub byes: usize,
+    pub elo: f64,
+}
+
+impl Standing {
+    pub fn points(&self) -> f64 {
+        (self.wins + self.byes) as f64 + self.draws as f64 / 2.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentReport {
+    pub format: Format,
+    pub seed: u64,
+    pub matches: Vec<MatchRecord>,
+    pub byes: Vec<(usize, usize)>, // (round, entrant)
+    pub standings: Vec<Standing>,  // Leaderboard order
+    pub champion: Option<usize>,   // The elimination winner, or the top of the leaderboard
+}
+
+pub const STARTING_ELO: f64 = 1500.0;
+const ELO_K: f64 = 32.0;
+const ELIMINATION_REPLAYS: usize = 3;
+
+pub struct Tournament {
+    names: Vec<String>,
+    format: Format,
+    seed: u64,
+}
+
+// The running state while a tournament is played
+struct Running<F> {
+    play: F,
+    seed: u64,
+    matches: Vec<MatchRecord>,
+    byes: Vec<(usize, usize)>,
+    standings: Vec<Standing>,
+}
+
+impl<F: FnMut(usize, usize, u64) -> MatchPlay> Running<F> {
+    fn play_match(&mut self, round: usize, first: usize, second: usize) -> Outcome {
+        let seed = splitmix64(self.seed ^ self.matches.len() as u64);
+        let MatchPlay { outcome, summary } = (self.play)(first, second, seed);
+
+        let score = match outcome {
+            Outcome::FirstWins => 1.0,
+            Outcome::SecondWins => 0.0,
+            Outcome::Draw => 0.5,
+        };
+        let (first_elo, second_elo) = (self.standings[first].elo, self.standings[second].elo);
+        let expected = 1.0 / (1.0 + 10f64.powf((second_elo - first_elo) / 400.0));
+        self.standings[first].elo += ELO_K * (score - expected);
+        self.standings[second].elo -= ELO_K * (score - expected);
+        for (entrant, won) in [(first, Outcome::FirstWins), (second, Outcome::SecondWins)] {
+            let standing = &mut self.standings[entrant];
+            standing.played += 1;
+            match outcome {
+                Outcome::Draw => standing.draws += 1,
+                outcome if outcome == won => standing.wins += 1,
+                _ => standing.losses += 1,
+            }
+        }
+
+        self.matches.push(MatchRecord {
+            round,
+            first,
+            second,
+            seed,
+            outcome,
+            summary,
+        });
+        outcome
+    }
+
+    fn bye(&mut self, round: usize, entrant: usize) {
+        self.standings[entrant].byes += 1;
+        self.byes.push((round, entrant));
+    }
+
+    fn round_robin(&mut self) {
+        let count = self.standings.len();
+        // Circle method: entrant 0 (or the bye) stays put, everyone else rotates one place a round
+        let mut circle: Vec<Option<usize>> = (0..count).map(Some).collect();
+        if count % 2 == 1 {
+            circle.push(None);
+        }
+        let size = circle.len();
+        for round in 1..size {
+            for slot in 0..size / 2 {
+                match (circle[slot], circle[size - 1 - slot]) {
+                    (Some(first), Some(second)) => {
+                        // Alternate who goes first so nobody is always seat 0
+                        if round % 2 == 0 {
+                            self.play_match(round, first, second);
+                        } else {
+                            self.play_match(round, second, first);
+                        }
+                    }
+                    (Some(entrant), None) | (None, Some(entrant)) => self.byes.push((round, entrant)),
+                    (None, None) => {}
+                }
+            }
+            circle[1..].rotate_right(1);
+        }
+    }
+
+    fn swiss(&mut self, rounds: usize) {
+        let mut met: HashSet<(usize, usize)> = HashSet::new();
+        for round in 1..=rounds {
+            let mut order: Vec<usize> = leaderboard(&self.standings).iter().map(|standing| standing.entrant).collect();
+            if order.len() % 2 == 1 {
+                // Lowest placed player without a bye yet, or the lowest if everyone has had one
+                let position = order
+                    .iter()
+                    .rposition(|entrant| self.standings[*entrant].byes == 0)
+                    .unwrap_or(order.len() - 1);
+                let entrant = order.remove(position);
+                self.bye(round, entrant);
+            }
+            while !order.is_empty() {
+                let first = order.remove(0);
+                // The next highest they haven't met, or the next highest if they've met everyone left
+                let position = order
+                    .iter()
+                    .position(|other| !met.contains(&(first.min(*other), first.max(*other))))
+                    .unwrap_or(0);
+                let second = order.remove(position);
+                met.insert((first.min(second), first.max(second)));
+                self.play_match(round, first, second);
+            }
+        }
+    }
+
+    fn elimination(&mut self) -> Option<usize> {
+        let count = self.standings.len();
+        let size = count.next_power_of_two();
+        let mut alive: Vec<Option<usize>> = bracket(size)
+            .into_iter()
+            .map(|seed| if seed < count { Some(seed) } else { None })
+            .collect();
+        let mut round = 1;
+        while alive.len() > 1 {
+            let mut next = vec![];
+            for pair in alive.chunks(2) {
+                next.push(match (pair[0], pair[1]) {
+                    (Some(first), Some(second)) => Some(self.knockout(round, first, second)),
+                    (Some(entrant), None) | (None, Some(entrant)) => {
+                        self.byes.push((round, entrant)); // Straight through, no points for it
+                        Some(entrant)
+                    }
+                    (None, None) => None,
+                });
+            }
+            alive = next;
+            round += 1;
+        }
+        alive[0]
+    }
+
+    // Plays until someone wins, the higher seed goes through after too many draws
+    fn knockout(&mut self, round: usize, first: usize, second: usize) -> usize {
+        for _ in 0..ELIMINATION_REPLAYS {
+            match self.play_match(round, first, second) {
+                Outcome::FirstWins => return first,
+                Outcome::SecondWins => return second,
+                Outcome::Draw => {}
+            }
+        }
+        first.min(second)
+    }
+}
+
+// Bracket positions for `size` (a power of two) seeds so the top seeds meet as late as possible:
+// 2 -> [0, 1], 4 -> [0, 3, 1, 2], 8 -> [0, 7, 3, 4, 1, 6, 2, 5]
+fn bracket(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let next = order.len() * 2;
+        order = order.iter().flat_map(|seed| [*seed, next - 1 - seed]).collect();
+    }
+    order
+}
+
+fn leaderboard(standings: &[Standing]) -> Vec<Standing> {
+    let mut sorted = standings.to_vec();
+    sorted.sort_by(|a, b| {
+        b.points()
+            .total_cmp(&a.points())
+            .then(b.elo.total_cmp(&a.elo))
+            .then(a.entrant.cmp(&b.entrant))
+    });
+    sorted
+}
+
+impl Tournament {
+    pub fn new(names: Vec<String>, format: Format, seed: u64) -> Result<Self, TournamentError> {
+        if names.len() < 2 {
+            return Err(TournamentError::NotEnoughEntrants(names.len()));
+        }
+        Ok(Tournament { names, format, seed })
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Plays every match with `play(first, second, seed)`
+    pub fn run<F: FnMut(usize, usize, u64) -> MatchPlay>(&self, play: F) -> TournamentReport {
+        let mut running = Running {
+            play,
+            seed: self.seed,
+            matches: vec![],
+            byes: vec![],
+            standings: self
+                .names
+                .iter()
+                .enumerate()
+                .map(|(entrant, name)| Standing {
+                    entrant,
+                    name: name.clone(),
+                    played: 0,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    byes: 0,
+                    elo: STARTING_ELO,
+                })
+                .collect(),
+        };
+
+        let champion = match self.format {
+            Format::RoundRobin => {
+                running.round_robin();
+                None
+            }
+            Format::Swiss { rounds } => {
+                let rounds = rounds.unwrap_or(self.names.len().next_power_of_two().trailing_zeros() as usize);
+                running.swiss(rounds.max(1));
+                None
+            }
+            Format::Elimination => running.elimination(),
+        };
+        let standings = leaderboard(&running.standings);
+        TournamentReport {
+            format: self.format,
+            seed: self.seed,
+            champion: champion.or(standings.first().map(|standing| standing.entrant)),
+            matches: running.matches,
+            byes: running.byes,
+            standings,
+        }
+    }
+}
+
+impl TournamentReport {
+    fn name(&self, entrant: usize) -> &str {
+        self.standings
+            .iter()
+            .find(|standing| standing.entrant == entrant)
+            .map_or("?", |standing| standing.name.as_str())
+    }
+}
+
+impl fmt::Display for TournamentReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} tournament, {} entrants, seed {}",
+            self.format,
+            self.standings.len(),
+            self.seed
+        )?;
+        let rounds = self.matches.iter().map(|record| record.round).max().unwrap_or(0);
+        for round in 1..=rounds {
+            writeln!(f, "Round {}", round)?;
+            for record in self.matches.iter().filter(|record| record.round == round) {
+                let result = match record.winner() {
+                    Some(winner) => format!("{} wins", self.name(winner)),
+                    None => String::from("draw"),
+                };
+                writeln!(
+                    f,
+                    "  {:>12} v {:<12} {:<16} {:<20} replay seed {}",
+                    self.name(record.first),
+                    self.name(record.second),
+                    result,
+                    record.summary,
+                    record.seed
+                )?;
+            }
+            for (_, entrant) in self.byes.iter().filter(|(bye_round, _)| *bye_round == round) {
+                writeln!(f, "  {:>12} has a bye", self.name(*entrant))?;
+            }
+        }
+
+        writeln!(f, "\n  # {:<12} {:>3} {:>3} {:>3} {:>3} {:>5} {:>6}", "Name", "P", "W", "D", "L", "Pts", "Elo")?;
+        for (place, standing) in self.standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3} {:<12} {:>3} {:>3} {:>3} {:>3} {:>5.1} {:>6.0}",
+                place + 1,
+                standing.name,
+                standing.played,
+                standing.wins,
+                standing.draws,
+                standing.losses,
+                standing.points(),
+                standing.elo
+            )?;
+        }
+        if let Some(champion) = self.champion {
+            write!(f, "Winner: {}", self.name(champion))?;
+        }
+        Ok(())
+    }
+}
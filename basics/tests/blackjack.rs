@@ -0,0 +1,32 @@
+use basics::blackjack::{BlackjackError, Game, PlayerAction, Rules};
+use basics::card::Card;
+use basics::deck::Deck;
+
+// The last code is the top card, dealt first
+fn stacked(codes: &str) -> Deck {
+    Deck::from_cards(codes.split_whitespace().map(|code| code.parse::<Card>().unwrap()).collect())
+}
+
+#[test]
+fn running_out_on_the_deal_keeps_the_bet() {
+    let mut game = Game::new(stacked("TH 8D TC"), Rules::default(), 100);
+    assert!(matches!(game.start_round(10), Err(BlackjackError::OutOfCards)));
+    assert_eq!(game.bankroll(), 100);
+    assert!(!game.in_round());
+}
+
+#[test]
+fn running_out_while_the_dealer_draws_calls_the_round_off() {
+    // Player 10 8, dealer 10 6 has to hit with nothing left
+    let mut game = Game::new(stacked("6S TH 8D TC"), Rules::default(), 100);
+    assert!(game.start_round(10).unwrap().is_none());
+    assert!(matches!(game.act(PlayerAction::Stand), Err(BlackjackError::OutOfCards)));
+    assert!(!game.in_round());
+    assert_eq!(game.bankroll(), 100);
+
+    // The round's cards are back in the discards, so play carries on
+    game.start_round(10).unwrap();
+    assert!(game.in_round());
+    assert_eq!(game.bankroll(), 90);
+    assert!(!game.available_actions().is_empty());
+}
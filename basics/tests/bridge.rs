@@ -0,0 +1,25 @@
+use basics::bridge::BridgeDeal;
+use basics::deck::Deck;
+
+#[test]
+fn pbn_writes_the_mandatory_tags_in_order() {
+    let deal = BridgeDeal::deal(&mut Deck::with_seed(3), 5).unwrap();
+    let pbn = deal.to_pbn();
+    let tags: Vec<&str> = pbn
+        .lines()
+        .map(|line| line.trim_start_matches('[').split(' ').next().unwrap())
+        .collect();
+    assert_eq!(
+        tags,
+        [
+            "Event", "Site", "Date", "Board", "West", "North", "East", "South", "Dealer", "Vulnerable", "Deal",
+            "Scoring", "Declarer", "Contract", "Result"
+        ]
+    );
+}
+
+#[test]
+fn pbn_reads_back_what_it_wrote() {
+    let deal = BridgeDeal::deal(&mut Deck::with_seed(3), 5).unwrap();
+    assert_eq!(BridgeDeal::from_pbn(&deal.to_pbn()).unwrap(), deal);
+}
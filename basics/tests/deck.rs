@@ -0,0 +1,19 @@
+use basics::card::{Card, Rank, Suit};
+use basics::deck::Deck;
+use basics::odds;
+
+#[test]
+fn a_whole_deck_counts_what_it_has_dealt() {
+    let mut deck = Deck::with_seed(3);
+    assert_eq!(deck.running_count(), Some(0));
+    deck.shuffle();
+    let dealt = deck.deal(10).unwrap();
+    assert_eq!(deck.running_count(), Some(odds::running_count(&dealt)));
+}
+
+#[test]
+fn a_deck_of_some_of_the_cards_has_no_count() {
+    let deck = Deck::from_cards(vec![Card::new(Rank::Two, Suit::Hearts), Card::new(Rank::Ace, Suit::Spades)]);
+    assert_eq!(deck.running_count(), None);
+    assert_eq!(deck.true_count(), None);
+}
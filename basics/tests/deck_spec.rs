@@ -0,0 +1,25 @@
+use basics::deck_spec::DeckSpec;
+use std::path::Path;
+
+fn uno() -> DeckSpec {
+    DeckSpec::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("decks/uno.deck")).unwrap()
+}
+
+#[test]
+fn a_spec_builds_a_deck_that_shuffles_and_deals() {
+    let mut deck = uno().build().unwrap();
+    assert_eq!(deck.remaining(), 108);
+    deck.shuffle_seeded(7);
+    let hand = deck.deal(7).unwrap();
+    assert_eq!(deck.remaining(), 101);
+
+    let mut again = uno().build().unwrap();
+    again.shuffle_seeded(7);
+    assert_eq!(again.deal(7).unwrap(), hand);
+}
+
+#[test]
+fn a_spec_with_no_cards_does_not_build() {
+    let err = DeckSpec::new("Empty").suits(&["Cups"]).build().unwrap_err();
+    assert_eq!(err.to_string(), "deck 'Empty' has no cards");
+}
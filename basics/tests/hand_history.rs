@@ -0,0 +1,88 @@
+use basics::draw_poker::{self, KeepGroups, StandPat};
+use basics::hand_history::{self, HandHistory};
+
+fn header(time: &str) -> String {
+    format!(
+        "PokerStars Hand #1: 5 Card Draw (ante 5) - {} UTC\nTable 'T' 2-max Seat #1 is the button\nSeat 1: Ann (100 in chips)\n",
+        time
+    )
+}
+
+#[test]
+fn times_that_do_not_exist_are_rejected() {
+    for time in [
+        "2026/13/40 99:99:99",
+        "2026/13/01 12:00:00",
+        "2026/00/01 12:00:00",
+        "2026/04/31 12:00:00",
+        "2026/02/29 12:00:00",
+        "2026/10/00 12:00:00",
+        "2026/10/19 24:00:00",
+        "2026/10/19 12:60:00",
+        "2026/10/19 12:00:60",
+    ] {
+        assert!(header(time).parse::<HandHistory>().is_err(), "{} was accepted", time);
+    }
+}
+
+#[test]
+fn real_times_read_back() {
+    for time in ["2024/02/29 00:00:00", "2026/10/19 23:59:59", "1970/01/01 00:00:00"] {
+        let hand: HandHistory = header(time).parse().unwrap();
+        assert!(hand.to_text().contains(time));
+    }
+}
+
+#[test]
+fn heads_up_histories_only_depend_on_the_arguments() {
+    let play = || draw_poker::heads_up([("Groups", &KeepGroups), ("Pat", &StandPat)], 100, 5, 5, 21, 1_792_418_631);
+    let (first, second) = (play(), play());
+    assert_eq!(first, second);
+    assert!(first.histories.iter().all(|hand| hand.time == 1_792_418_631));
+    let text = hand_history::write_all(&first.histories);
+    assert_eq!(hand_history::parse_all(&text).unwrap(), first.histories);
+}
+
+#[test]
+fn times_in_other_zones_are_rejected() {
+    let hand = header("2026/10/19 20:15:00").replace(" UTC\n", " ET\n");
+    let err = hand.parse::<HandHistory>().unwrap_err();
+    assert_eq!(err.reason, "'2026/10/19 20:15:00 ET' is in ET, only UTC times can be read");
+}
+
+fn holdem(turn: &str) -> String {
+    format!(
+        "{}Seat 2: Bo (100 in chips)
+Ann: posts small blind 5
+Bo: posts big blind 10
+Ann: raises 10 to 20
+Bo: calls 10
+*** FLOP *** [Qh Jh 2c]
+Bo: bets 20
+Ann: raises 40 to 60
+Bo: calls 40
+{}
+Bo: checks
+Ann: checks
+",
+        header("2026/10/19 20:15:00"),
+        turn
+    )
+}
+
+#[test]
+fn raises_put_in_what_they_raise_to_less_what_was_already_in() {
+    let hand: HandHistory = holdem("*** TURN *** [Qh Jh 2c] [Th]").parse().unwrap();
+    assert_eq!(hand.pot(), 160);
+    let text = hand.to_text();
+    assert!(text.contains("Ann: raises 40 to 60\n"));
+    assert!(text.contains("*** TURN *** [Qh Jh 2c] [Th]\n"));
+    assert_eq!(text.parse::<HandHistory>().unwrap(), hand);
+}
+
+#[test]
+fn a_street_that_shows_the_wrong_board_is_rejected() {
+    let err = holdem("*** TURN *** [Qh Jh 3c] [Th]").parse::<HandHistory>().unwrap_err();
+    assert_eq!(err.line, 13);
+    assert_eq!(err.reason, "the board so far is [Qh Jh 2c], not [Qh Jh 3c]");
+}
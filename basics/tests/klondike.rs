@@ -0,0 +1,29 @@
+use basics::deck::Deck;
+use basics::klondike::{Board, Klondike, KlondikeError, Rules};
+
+#[test]
+fn drawing_no_cards_is_rejected() {
+    let rules = Rules { draw: 0, redeals: None };
+    assert!(matches!(Klondike::seeded(1, rules), Err(KlondikeError::BadRules(_))));
+}
+
+#[test]
+fn a_partial_deck_is_rejected() {
+    let mut deck = Deck::new();
+    deck.deal(30).unwrap();
+    assert!(matches!(Board::deal(deck, Rules::default()), Err(KlondikeError::BadDeck(_))));
+}
+
+#[test]
+fn a_repeated_card_is_rejected() {
+    let mut cards = Deck::new().into_cards();
+    cards[0] = cards[1];
+    assert!(matches!(Board::deal(Deck::from_cards(cards), Rules::default()), Err(KlondikeError::BadDeck(_))));
+}
+
+#[test]
+fn a_full_deck_deals() {
+    let board = Board::deal(Deck::new(), Rules::default()).unwrap();
+    assert_eq!(board.stock.len(), 24);
+    assert_eq!(board.tableau.iter().map(|column| column.cards.len()).sum::<usize>(), 28);
+}
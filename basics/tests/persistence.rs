@@ -0,0 +1,45 @@
+use basics::deck::Deck;
+use basics::persistence::SavedGame;
+
+#[test]
+fn binary_keeps_the_rng_position() {
+    let mut deck = Deck::with_seed(9);
+    deck.shuffle();
+    let saved = SavedGame::capture(&deck, &[]);
+    assert_eq!(SavedGame::from_bytes(&saved.to_bytes().unwrap()).unwrap(), saved);
+}
+
+#[test]
+fn binary_keeps_a_seed_without_an_rng() {
+    let mut saved = SavedGame::capture(&Deck::new(), &[]);
+    saved.seed = Some(42);
+    let loaded = SavedGame::from_bytes(&saved.to_bytes().unwrap()).unwrap();
+    assert_eq!(loaded.seed, Some(42));
+    assert_eq!(loaded.rng, None);
+}
+
+#[test]
+fn an_rng_without_a_seed_is_not_saved() {
+    let mut saved = SavedGame::capture(&Deck::with_seed(9), &[]);
+    saved.seed = None;
+    assert!(saved.to_bytes().is_err());
+}
+
+#[test]
+fn a_deck_shuffled_with_an_outside_rng_drops_its_seed() {
+    let mut deck = Deck::with_seed(9);
+    basics::commitment::secure_shuffle(&mut deck);
+    assert_eq!(deck.seed(), None);
+    let saved = SavedGame::capture(&deck, &[]);
+    assert_eq!((saved.seed, saved.rng), (None, None));
+}
+
+#[test]
+fn a_loaded_deck_keeps_its_count() {
+    let mut deck = Deck::with_seed(5);
+    deck.shuffle();
+    let hand = deck.deal(5).unwrap();
+    let saved = SavedGame::capture(&deck, &[("Ann", &hand)]);
+    let (loaded, _) = SavedGame::from_bytes(&saved.to_bytes().unwrap()).unwrap().restore().unwrap();
+    assert_eq!(loaded.running_count(), deck.running_count());
+}
@@ -0,0 +1,116 @@
+use basics::card::Card;
+use basics::deck::Deck;
+use basics::poker::{self, Category};
+use std::collections::{HashMap, HashSet};
+
+// Known counts for every possible 5 card hand from one deck
+const EXPECTED: [(Category, usize); 9] = [
+    (Category::StraightFlush, 40),
+    (Category::FourOfAKind, 624),
+    (Category::FullHouse, 3_744),
+    (Category::Flush, 5_108),
+    (Category::Straight, 10_200),
+    (Category::ThreeOfAKind, 54_912),
+    (Category::TwoPair, 123_552),
+    (Category::OnePair, 1_098_240),
+    (Category::HighCard, 1_302_540),
+];
+const TOTAL_HANDS: usize = 2_598_960;
+const DISTINCT_HANDS: usize = 7_462; // Hands that are genuinely different strengths
+
+fn hand(codes: &str) -> [Card; 5] {
+    let cards: Vec<Card> = codes.split_whitespace().map(|code| code.parse().unwrap()).collect();
+    cards.try_into().unwrap()
+}
+
+// Every 5 card hand, about 2.6 million evaluations (a few seconds in a debug build)
+#[test]
+fn census_matches_known_hand_counts() {
+    let cards: Vec<Card> = Deck::new().into_cards();
+    let mut counts: HashMap<Category, usize> = HashMap::new();
+    let mut distinct = HashSet::new();
+    let mut total = 0;
+
+    for a in 0..52 {
+        for b in a + 1..52 {
+            for c in b + 1..52 {
+                for d in c + 1..52 {
+                    for e in d + 1..52 {
+                        let rank = poker::evaluate_5(&[cards[a], cards[b], cards[c], cards[d], cards[e]]);
+                        *counts.entry(rank.category()).or_insert(0) += 1;
+                        distinct.insert(rank);
+                        total += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    for (category, expected) in EXPECTED {
+        assert_eq!(counts.get(&category).copied().unwrap_or(0), expected, "{}", category);
+    }
+    assert_eq!(counts.get(&Category::FiveOfAKind), None);
+    assert_eq!(total, TOTAL_HANDS);
+    assert_eq!(distinct.len(), DISTINCT_HANDS);
+}
+
+#[test]
+fn categories_rank_in_order() {
+    let hands = [
+        "2C 4D 6H 8S TC",
+        "2C 2D 6H 8S TC",
+        "2C 2D 6H 6S TC",
+        "2C 2D 2H 8S TC",
+        "AC 2D 3H 4S 5C",
+        "2C 4C 6C 8C TC",
+        "2C 2D 2H 8S 8C",
+        "2C 2D 2H 2S TC",
+        "9C TC JC QC KC",
+    ];
+    let ranks: Vec<_> = hands.iter().map(|codes| poker::evaluate_5(&hand(codes))).collect();
+    for (rank, category) in ranks.iter().zip(EXPECTED.iter().rev().map(|(category, _)| *category)) {
+        assert_eq!(rank.category(), category);
+    }
+    assert!(ranks.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn wheel_is_the_lowest_straight() {
+    let wheel = poker::evaluate_5(&hand("AC 2D 3H 4S 5C"));
+    let six_high = poker::evaluate_5(&hand("2C 3D 4H 5S 6C"));
+    assert_eq!(wheel.category(), Category::Straight);
+    assert!(wheel < six_high);
+}
+
+#[test]
+fn best_of_seven_finds_the_flush() {
+    let cards: Vec<Card> = "AH KH 2C 7H 9H 2D 4H"
+        .split_whitespace()
+        .map(|code| code.parse().unwrap())
+        .collect();
+    assert_eq!(poker::best_of(&cards).unwrap().category(), Category::Flush);
+    assert_eq!(poker::best_of(&cards[..4]), None);
+}
+
+// Hands below need the same card twice, as a multi-deck shoe deals them
+
+#[test]
+fn five_of_a_kind_beats_a_straight_flush() {
+    let five = poker::evaluate_5(&hand("7C 7D 7H 7S 7C"));
+    assert_eq!(five.category(), Category::FiveOfAKind);
+    assert!(five > poker::evaluate_5(&hand("TS JS QS KS AS")));
+    assert!(five < poker::evaluate_5(&hand("8C 8D 8H 8S 8S")));
+}
+
+#[test]
+fn suited_hands_with_repeated_ranks() {
+    // A pair, two pair or trips in one suit is a flush, and the repeats count as kickers
+    let suited_pair = poker::evaluate_5(&hand("AS AS KS 7S 2S"));
+    assert_eq!(suited_pair.category(), Category::Flush);
+    assert!(suited_pair > poker::evaluate_5(&hand("AS KS QS 7S 2S")));
+    assert_eq!(poker::evaluate_5(&hand("9H 9H 9H 4H 2H")).category(), Category::Flush);
+
+    // A full house or four of a kind is still better than the flush
+    assert_eq!(poker::evaluate_5(&hand("9H 9H 9H 4H 4H")).category(), Category::FullHouse);
+    assert_eq!(poker::evaluate_5(&hand("9H 9H 9H 9H 4H")).category(), Category::FourOfAKind);
+}
@@ -0,0 +1,131 @@
+use basics::server;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+// A server on a free port, left running for the rest of the test run
+fn start() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || server::serve(listener));
+    address
+}
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    seen: Vec<String>, // Every line before the last reply
+}
+
+impl Client {
+    fn connect(address: &str) -> Client {
+        let stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        Client {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+            seen: vec![],
+        }
+    }
+
+    fn line(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+
+    // Sends a command and returns its OK or ERR reply, keeping the lines that came before it
+    fn send(&mut self, command: &str) -> String {
+        writeln!(self.writer, "{}", command).unwrap();
+        self.seen.clear();
+        loop {
+            let line = self.line();
+            if line.starts_with("OK") || line.starts_with("ERR") {
+                return line;
+            }
+            assert!(!line.is_empty(), "connection closed waiting for a reply to {}", command);
+            self.seen.push(line);
+        }
+    }
+
+    // Reads until a line starting with `prefix`
+    fn wait_for(&mut self, prefix: &str) -> String {
+        if let Some(line) = self.seen.iter().find(|line| line.starts_with(prefix)) {
+            return line.clone();
+        }
+        loop {
+            let line = self.line();
+            assert!(!line.is_empty(), "connection closed waiting for {}", prefix);
+            if line.starts_with(prefix) {
+                return line;
+            }
+        }
+    }
+}
+
+fn token(reply: &str) -> String {
+    reply.split_whitespace().last().unwrap().to_string()
+}
+
+#[test]
+fn join_act_leave_and_reconnect() {
+    let address = start();
+    let mut ann = Client::connect(&address);
+    let mut bob = Client::connect(&address);
+    assert!(ann.send("JOIN main Ann").starts_with("OK SEAT 0 TOKEN "));
+    let bob_joined = bob.send("JOIN main Bob");
+    assert!(bob_joined.starts_with("OK SEAT 1 TOKEN "));
+    assert_eq!(ann.wait_for("EVENT JOINED 1"), "EVENT JOINED 1 Bob");
+
+    assert_eq!(ann.send("START"), "OK STARTED");
+    // Seat 1 is left of the button so Bob goes first
+    assert_eq!(bob.wait_for("EVENT TURN"), "EVENT TURN 1");
+    assert_eq!(ann.send("ACT stand"), "ERR it's seat 1's turn, not seat 0");
+
+    // Bob drops and comes back with his token
+    drop(bob);
+    assert_eq!(ann.wait_for("EVENT AWAY"), "EVENT AWAY 1");
+    let mut bob = Client::connect(&address);
+    assert_eq!(bob.send(&format!("RESUME {}", token(&bob_joined))), "OK SEAT 1 TABLE main");
+    assert!(bob.seen.iter().any(|line| line.starts_with("CARDS ")));
+    assert_eq!(ann.wait_for("EVENT BACK"), "EVENT BACK 1");
+
+    assert_eq!(bob.send("ACT fold"), "OK ACTED");
+    assert_eq!(ann.wait_for("EVENT RESULT"), "EVENT RESULT POT 4 WINNERS 0");
+    assert_eq!(bob.send("LEAVE"), "OK LEFT");
+    assert_eq!(ann.wait_for("EVENT LEFT"), "EVENT LEFT 1");
+    assert_eq!(bob.send("STATE"), "ERR JOIN or RESUME first");
+    assert_eq!(ann.send("QUIT"), "OK BYE");
+}
+
+#[test]
+fn a_line_that_is_too_long_closes_the_connection() {
+    let address = start();
+    let mut client = Client::connect(&address);
+    // Just past the limit and no more, so the server has read everything sent when it closes
+    client.writer.write_all("X".repeat(1025).as_bytes()).unwrap();
+    assert_eq!(client.line(), "ERR line too long");
+    assert_eq!(client.line(), "");
+}
+
+#[test]
+fn the_server_stands_for_a_player_who_is_away_on_their_turn() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || server::serve_with(listener, Duration::from_millis(200)));
+
+    let mut ann = Client::connect(&address);
+    let mut bob = Client::connect(&address);
+    ann.send("JOIN main Ann");
+    bob.send("JOIN main Bob");
+    assert_eq!(ann.send("START"), "OK STARTED");
+    assert_eq!(ann.wait_for("EVENT TURN"), "EVENT TURN 1");
+
+    drop(bob);
+    assert_eq!(ann.wait_for("EVENT AWAY"), "EVENT AWAY 1");
+    assert_eq!(ann.wait_for("EVENT ACTED"), "EVENT ACTED 1 stand");
+    assert_eq!(ann.line(), "EVENT TURN 0");
+    assert_eq!(ann.send("ACT stand"), "OK ACTED");
+    assert!(ann.wait_for("EVENT RESULT").starts_with("EVENT RESULT POT 4 WINNERS"));
+}
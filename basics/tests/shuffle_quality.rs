@@ -0,0 +1,23 @@
+use basics::shuffle_quality::{self, QualityError};
+use basics::shuffles::Technique;
+
+#[test]
+fn too_small_a_deck_is_an_error() {
+    for cards in [0, 1] {
+        assert_eq!(
+            shuffle_quality::measure(Technique::FisherYates, 1, 100, cards, 1).unwrap_err(),
+            QualityError::TooFewCards(cards)
+        );
+    }
+    assert_eq!(
+        shuffle_quality::measure(Technique::FisherYates, 1, 0, 52, 1).unwrap_err(),
+        QualityError::NoTrials
+    );
+}
+
+#[test]
+fn two_cards_give_a_real_p_value() {
+    let report = shuffle_quality::measure(Technique::FisherYates, 1, 1_000, 2, 1).unwrap();
+    assert!(report.p_value.is_finite());
+    assert!(report.looks_random(0.001));
+}
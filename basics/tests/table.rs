@@ -0,0 +1,155 @@
+use basics::deck::Deck;
+use basics::hand_history::HandHistory;
+use basics::table::{CardGame, Progress, Table, TableError};
+
+// Everyone bets up to `stake` when the hand starts, the first to act ends it. The best hand is the
+// earliest seat in `ranking`, and seats missing from it can't win
+struct AllIn {
+    stake: i64,
+    ranking: Vec<usize>,
+}
+
+impl CardGame for AllIn {
+    type Action = bool; // True folds
+
+    fn start_hand(&mut self, table: &mut Table) -> Result<(), TableError> {
+        for seat in table.in_hand() {
+            let chips = table.player(seat)?.chips;
+            table.bet(seat, chips.min(self.stake))?;
+        }
+        Ok(())
+    }
+
+    fn act(&mut self, table: &mut Table, seat: usize, fold: bool) -> Result<Progress, TableError> {
+        if fold {
+            table.fold(seat)?;
+            return Ok(Progress::NextPlayer);
+        }
+        Ok(Progress::HandOver)
+    }
+
+    fn winners(&self, _table: &Table, contenders: &[usize]) -> Vec<usize> {
+        self.ranking.iter().copied().filter(|seat| contenders.contains(seat)).take(1).collect()
+    }
+}
+
+fn seated(chips: &[i64]) -> Table {
+    let mut table = Table::new(Deck::new(), chips.len());
+    for (seat, chips) in chips.iter().enumerate() {
+        table.sit(&format!("seat {}", seat), *chips).unwrap();
+    }
+    table
+}
+
+fn chips(table: &Table) -> Vec<i64> {
+    table.players().map(|(_, player)| player.chips).collect()
+}
+
+#[test]
+fn a_short_all_in_only_wins_the_main_pot() {
+    let mut table = seated(&[100, 30, 100]);
+    let mut game = AllIn {
+        stake: 100,
+        ranking: vec![1, 2, 0],
+    };
+    table.start_hand(&mut game).unwrap();
+    let seat = table.turn().unwrap();
+    let result = table.act(&mut game, seat, false).unwrap().unwrap();
+    assert_eq!(result.pot, 230);
+    assert_eq!(result.pots, vec![(90, vec![1]), (140, vec![2])]);
+    assert_eq!(chips(&table), vec![0, 90, 140]);
+}
+
+#[test]
+fn folded_chips_above_everyone_still_in_are_won() {
+    let mut table = seated(&[40, 100, 40]);
+    let mut game = AllIn {
+        stake: 100,
+        ranking: vec![2, 0],
+    };
+    table.start_hand(&mut game).unwrap();
+    // Seat 1 acts first, left of the button, and folds the biggest bet
+    table.act(&mut game, 1, true).unwrap();
+    let result = table.act(&mut game, 2, false).unwrap().unwrap();
+    assert_eq!(result.pot, 180);
+    assert_eq!(chips(&table), vec![0, 0, 180]);
+}
+
+#[test]
+fn a_pot_nobody_wins_is_refunded() {
+    let mut table = seated(&[100, 30, 100]);
+    let mut game = AllIn {
+        stake: 100,
+        ranking: vec![],
+    };
+    table.start_hand(&mut game).unwrap();
+    let seat = table.turn().unwrap();
+    let result = table.act(&mut game, seat, false).unwrap().unwrap();
+    assert!(result.winners.is_empty());
+    assert_eq!(table.pot(), 0);
+    assert_eq!(chips(&table), vec![100, 30, 100]);
+}
+
+#[test]
+fn any_game_records_its_hands() {
+    let mut table = seated(&[100, 30, 100]);
+    let mut game = AllIn {
+        stake: 100,
+        ranking: vec![1, 2, 0],
+    };
+    assert!(table.last_hand().is_none());
+    table.start_hand(&mut game).unwrap();
+    table.act(&mut game, 1, true).unwrap();
+    table.act(&mut game, 2, false).unwrap();
+
+    let history = table.last_hand().unwrap().to_history(1, "Test", 0);
+    assert_eq!(history.seats.len(), 3);
+    assert_eq!(history.pot(), 230);
+    let text = history.to_text();
+    assert!(text.contains("seat 1: folds"));
+    assert!(text.contains("seat 2 collected 230 from pot"));
+    assert_eq!(text.parse::<HandHistory>().unwrap(), history);
+}
+
+// Antes `ante` and ends the hand when the first player still in checks
+struct Antes {
+    ante: i64,
+}
+
+impl CardGame for Antes {
+    type Action = bool; // True folds
+
+    fn start_hand(&mut self, table: &mut Table) -> Result<(), TableError> {
+        for seat in table.in_hand() {
+            table.ante(seat, self.ante)?;
+        }
+        Ok(())
+    }
+
+    fn act(&mut self, table: &mut Table, seat: usize, fold: bool) -> Result<Progress, TableError> {
+        if fold {
+            table.fold(seat)?;
+            return Ok(Progress::NextPlayer);
+        }
+        Ok(Progress::HandOver)
+    }
+
+    fn winners(&self, _table: &Table, contenders: &[usize]) -> Vec<usize> {
+        contenders.iter().copied().take(1).collect()
+    }
+}
+
+#[test]
+fn a_folded_player_who_put_chips_in_waits_for_the_hand_to_end() {
+    let mut table = seated(&[100, 100, 100]);
+    let mut game = Antes { ante: 2 };
+    table.start_hand(&mut game).unwrap();
+    table.act(&mut game, 1, true).unwrap();
+    assert_eq!(table.leave(1).unwrap_err(), TableError::HandInProgress);
+
+    let result = table.act(&mut game, 2, false).unwrap().unwrap();
+    assert_eq!(result.pot, 6);
+    assert_eq!(result.pots, vec![(6, vec![2])]);
+    assert_eq!(chips(&table), vec![98, 98, 104]);
+    assert_eq!(table.leave(1).unwrap().chips, 98);
+}
@@ -0,0 +1,156 @@
+use crate::logs::{Level, LogEntry, Time};
+use regex::{Regex, RegexBuilder};
+use std::fmt;
+
+/** Picking log entries
+- An entry is kept when it is at least the minimum level, inside the time window and its message matches
+- The window includes both ends. An --until written as HH:MM runs to the end of that minute
+- invert flips only the message match, like grep -v, so the level and time window still apply
+*/
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Substring { text: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn substring(text: &str, ignore_case: bool) -> Pattern {
+        Pattern::Substring {
+            text: if ignore_case { text.to_lowercase() } else { text.to_string() },
+            ignore_case,
+        }
+    }
+
+    pub fn regex(pattern: &str, ignore_case: bool) -> Result<Pattern, FilterError> {
+        RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(Pattern::Regex)
+            .map_err(|reason_this_failed| FilterError::InvalidRegex(reason_this_failed.to_string()))
+    }
+
+    pub fn is_match(&self, message: &str) -> bool {
+        match self {
+            Pattern::Substring { text, ignore_case: true } => message.to_lowercase().contains(text.as_str()),
+            Pattern::Substring { text, ignore_case: false } => message.contains(text.as_str()),
+            Pattern::Regex(regex) => regex.is_match(message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterError {
+    InvalidRegex(String),
+    EmptyWindow { since: Time, until: Time },
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilterError::InvalidRegex(reason) => write!(f, "invalid regex: {}", reason),
+            FilterError::EmptyWindow { since, until } => {
+                write!(f, "--since {} is after --until {}", since, until)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub level: Level, // Minimum
+    pub since: Option<Time>,
+    pub until: Option<Time>,
+    pub pattern: Option<Pattern>,
+    pub invert: bool,
+}
+
+/// Keeps everything
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            level: Level::Debug,
+            since: None,
+            until: None,
+            pattern: None,
+            invert: false,
+        }
+    }
+}
+
+impl Filter {
+    /// A window that ends before it starts would silently match nothing
+    pub fn check(&self) -> Result<(), FilterError> {
+        match (self.since, self.until) {
+            (Some(since), Some(until)) if since > until => Err(FilterError::EmptyWindow { since, until }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let message_matches = self
+            .pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&entry.message));
+
+        entry.level >= self.level
+            && self.since.is_none_or(|since| entry.time >= since)
+            && self.until.is_none_or(|until| entry.time <= until)
+            && message_matches != self.invert
+    }
+
+    pub fn apply<'a>(&'a self, entries: &'a [LogEntry]) -> impl Iterator<Item = &'a LogEntry> + 'a {
+        entries.iter().filter(move |entry| self.matches(entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<LogEntry> {
+        ["INFO 14:30:00 disk ok", "ERROR 14:40:00 disk full", "ERROR 14:45:00 network down", "ERROR 15:10:00 cpu hot"]
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    fn messages<'a>(filter: &'a Filter, entries: &'a [LogEntry]) -> Vec<&'a str> {
+        filter.apply(entries).map(|entry| entry.message.as_str()).collect()
+    }
+
+    #[test]
+    fn invert_still_applies_the_level_and_time_window() {
+        let filter = Filter {
+            level: Level::Error,
+            since: "14:35".parse().ok(),
+            until: "14:50".parse().ok(),
+            pattern: Some(Pattern::substring("disk", false)),
+            invert: true,
+        };
+        assert_eq!(messages(&filter, &entries()), vec!["network down"]);
+    }
+
+    #[test]
+    fn the_window_includes_both_ends() {
+        let filter = Filter {
+            since: "14:40:00".parse().ok(),
+            until: "14:45:00".parse().ok(),
+            ..Filter::default()
+        };
+        assert_eq!(messages(&filter, &entries()), vec!["disk full", "network down"]);
+    }
+
+    #[test]
+    fn a_window_that_ends_before_it_starts_is_rejected() {
+        let (since, until) = ("14:50".parse().unwrap(), "14:35".parse().unwrap());
+        let filter = Filter {
+            since: Some(since),
+            until: Some(until),
+            ..Filter::default()
+        };
+        assert_eq!(filter.check(), Err(FilterError::EmptyWindow { since, until }));
+        assert_eq!(filter.check().unwrap_err().to_string(), "--since 14:50:00 is after --until 14:35:00");
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::str::FromStr;
+
+/** Log lines
+- Each line is LEVEL HH:MM:SS message, e.g. "WARNING 14:32:10 Missing configuration..."
+- Levels are ordered DEBUG < INFO < WARNING < ERROR so "this level or worse" is a comparison. WARN is read as WARNING
+- Blank lines are skipped and Windows line endings (\r\n) are fine
+- A line that doesn't parse doesn't stop the rest, parse_log() returns it as a LogError with its line number
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub const ALL: [Level; 4] = [Level::Debug, Level::Info, Level::Warning, Level::Error];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warning => "WARNING",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Level {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.eq_ignore_ascii_case("WARN") {
+            return Ok(Level::Warning);
+        }
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(text))
+            .ok_or_else(|| ParseError::UnknownLevel(text.to_string()))
+    }
+}
+
+/// Time of day, to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+/// "14:32:10", or "14:32" for the start of that minute. "9:05" works too
+impl FromStr for Time {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidTime(text.to_string());
+        let parts: Vec<u8> = text
+            .split(':')
+            .map(|part| if (1..=2).contains(&part.len()) { part.parse().ok() } else { None })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        let (hour, minute, second) = match parts[..] {
+            [hour, minute] => (hour, minute, 0),
+            [hour, minute, second] => (hour, minute, second),
+            _ => return Err(invalid()),
+        };
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(invalid());
+        }
+        Ok(Time { hour, minute, second })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnknownLevel(String),
+    MissingTime,
+    InvalidTime(String),
+    MissingMessage,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnknownLevel(level) => write!(f, "'{}' isn't a log level", level),
+            ParseError::MissingTime => write!(f, "no time after the level"),
+            ParseError::InvalidTime(time) => write!(f, "'{}' isn't a time, expected HH:MM:SS", time),
+            ParseError::MissingMessage => write!(f, "no message after the time"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line that didn't parse, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogError {
+    pub line: usize, // From 1
+    pub text: String,
+    pub reason: ParseError,
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {} in '{}'", self.line, self.reason, self.text)
+    }
+}
+
+impl std::error::Error for LogError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub level: Level,
+    pub time: Time,
+    pub message: String,
+}
+
+/// Writes the entry back the way it was read
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.level, self.time, self.message)
+    }
+}
+
+impl FromStr for LogEntry {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut parts = line.trim().splitn(3, char::is_whitespace);
+        let level = parts.next().unwrap_or_default().parse()?;
+        let time = parts.next().ok_or(ParseError::MissingTime)?.parse()?;
+        let message = parts.next().map(str::trim).unwrap_or_default();
+        if message.is_empty() {
+            return Err(ParseError::MissingMessage);
+        }
+        Ok(LogEntry {
+            level,
+            time,
+            message: message.to_string(),
+        })
+    }
+}
+
+/// Every entry that parsed, and every line that didn't
+pub fn parse_log(text: &str) -> (Vec<LogEntry>, Vec<LogError>) {
+    let mut entries = vec![];
+    let mut errors = vec![];
+    // lines() drops the \r of a \r\n too
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.parse() {
+            Ok(entry) => entries.push(entry),
+            Err(reason_this_failed) => errors.push(LogError {
+                line: index + 1,
+                text: line.trim().to_string(),
+                reason: reason_this_failed,
+            }),
+        }
+    }
+    (entries, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crlf_and_blank_lines_are_fine() {
+        let (entries, errors) = parse_log("INFO 09:00:00 started\r\n\r\n   \r\nERROR 09:00:05 failed\r\n");
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "started");
+        assert_eq!(entries[1].to_string(), "ERROR 09:00:05 failed");
+    }
+
+    #[test]
+    fn a_bad_line_is_reported_with_its_number_and_the_rest_still_parse() {
+        let (entries, errors) = parse_log("INFO 09:00:00 started\n\nLOUD 09:00:01 what\nERROR 09:00:05 failed\n");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            errors,
+            vec![LogError {
+                line: 3,
+                text: String::from("LOUD 09:00:01 what"),
+                reason: ParseError::UnknownLevel(String::from("LOUD")),
+            }]
+        );
+        assert_eq!(errors[0].to_string(), "line 3: 'LOUD' isn't a log level in 'LOUD 09:00:01 what'");
+    }
+
+    #[test]
+    fn warn_is_read_as_warning() {
+        let entry: LogEntry = "warn 14:32:10 Missing configuration".parse().unwrap();
+        assert_eq!(entry.level, Level::Warning);
+        assert_eq!(entry.to_string(), "WARNING 14:32:10 Missing configuration");
+    }
+
+    #[test]
+    fn times_that_do_not_exist_are_rejected() {
+        for time in ["24:00:00", "12:60", "12:00:60", "12", "12:00:00:00", "123:00", "ab:cd", ""] {
+            assert_eq!(time.parse::<Time>(), Err(ParseError::InvalidTime(time.to_string())), "{}", time);
+        }
+        assert_eq!("INFO 25:00:00 late".parse::<LogEntry>(), Err(ParseError::InvalidTime(String::from("25:00:00"))));
+        assert_eq!("INFO".parse::<LogEntry>(), Err(ParseError::MissingTime));
+        assert_eq!("INFO 09:00".parse::<LogEntry>(), Err(ParseError::MissingMessage));
+    }
+}
@@ -1,5 +1,13 @@
+mod filter;
+mod logs;
+
+use filter::{Filter, FilterError, Pattern};
+use logs::{ParseError, Time};
+use std::env;
+use std::fmt;
 use std::fs;
-use std::io::Error;
+use std::io::{self, Error};
+use std::process;
 
 /** Notes on computer memory
 - Stack stores metadata about a data structure
@@ -15,11 +23,167 @@ Notes on error handling
 
 */
 
+/*
+Filters a log file
+- cargo run -p error_handling -- [INPUT] [--output FILE] [--level LEVEL] [--since HH:MM[:SS]] [--until HH:MM[:SS]]
+      [--match TEXT | --regex PATTERN] [--ignore-case] [--invert] [--count]
+- INPUT defaults to error_handling/logs.txt, - reads stdin. Entries go to stdout unless --output is given
+- --level keeps that level and worse, e.g. --level warning keeps WARNING and ERROR
+- --invert keeps the entries whose message doesn't match, --count prints how many entries were kept instead
+- Lines that aren't log entries are reported on stderr with their line number
+- --demo runs the error handling examples instead
+ */
 fn main() -> Result<(), Error> { //Rust main can return this type
-    // let text = fs::read_to_string("logs.txt");
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--demo") {
+        return demo();
+    }
+
+    let options = match Options::from_args(&args) {
+        Ok(options) => options,
+        Err(reason_this_failed) => {
+            eprintln!("{}", reason_this_failed);
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let text = if options.input == "-" {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(&options.input)
+    };
+    let text = match text {
+        Ok(text_that_was_read) => text_that_was_read,
+        Err(why_this_failed) => {
+            eprintln!("{}: {}", options.input, why_this_failed);
+            process::exit(1);
+        }
+    };
+
+    let (entries, problems) = logs::parse_log(&text);
+    for problem in problems {
+        eprintln!("{}: {}", options.input, problem);
+    }
+
+    let kept = options.filter.apply(&entries);
+    let output = if options.count {
+        format!("{}\n", kept.count())
+    } else {
+        kept.map(|entry| format!("{}\n", entry)).collect()
+    };
 
-    // println!("{:#?}", text);
+    match &options.output {
+        Some(path) => fs::write(path, output)?, // '?' hands the failure back to main's caller
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+const USAGE: &str = "usage: error_handling [INPUT] [--output FILE] [--level LEVEL] [--since HH:MM[:SS]] \
+[--until HH:MM[:SS]] [--match TEXT | --regex PATTERN] [--ignore-case] [--invert] [--count]";
+
+#[derive(Debug)]
+struct Options {
+    input: String,
+    output: Option<String>,
+    filter: Filter,
+    count: bool,
+}
+
+#[derive(Debug)]
+enum UsageError {
+    MissingValue(String),
+    InvalidValue { flag: String, reason: ParseError },
+    UnknownFlag(String),
+    ExtraInput(String),
+    TwoPatterns,
+    InvertWithoutPattern,
+    Filter(FilterError),
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UsageError::MissingValue(flag) => write!(f, "{} needs a value", flag),
+            UsageError::InvalidValue { flag, reason } => write!(f, "{}: {}", flag, reason),
+            UsageError::UnknownFlag(flag) => write!(f, "unknown option {}", flag),
+            UsageError::ExtraInput(path) => write!(f, "only one input file, '{}' is extra", path),
+            UsageError::TwoPatterns => write!(f, "use --match or --regex, not both"),
+            UsageError::InvertWithoutPattern => write!(f, "--invert needs --match or --regex"),
+            UsageError::Filter(reason) => write!(f, "{}", reason),
+        }
+    }
+}
 
+impl From<FilterError> for UsageError {
+    fn from(reason: FilterError) -> Self {
+        UsageError::Filter(reason)
+    }
+}
+
+impl Options {
+    fn from_args(args: &[String]) -> Result<Options, UsageError> {
+        let mut input = None;
+        let mut output = None;
+        let mut filter = Filter::default();
+        let mut count = false;
+        let mut ignore_case = false;
+        let mut substring = None;
+        let mut regex = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| UsageError::MissingValue(arg.clone()));
+            let invalid = |reason| UsageError::InvalidValue { flag: arg.clone(), reason };
+            match arg.as_str() {
+                "--output" | "-o" => output = Some(value()?.clone()),
+                "--level" | "-l" => filter.level = value()?.parse().map_err(invalid)?,
+                "--since" => filter.since = Some(value()?.parse().map_err(invalid)?),
+                "--until" => {
+                    let text = value()?;
+                    let mut until: Time = text.parse().map_err(invalid)?;
+                    if text.matches(':').count() == 1 {
+                        until.second = 59; // HH:MM means up to the end of that minute
+                    }
+                    filter.until = Some(until);
+                }
+                "--match" | "-m" => substring = Some(value()?.clone()),
+                "--regex" | "-e" => regex = Some(value()?.clone()),
+                "--ignore-case" | "-i" => ignore_case = true,
+                "--invert" | "-v" => filter.invert = true,
+                "--count" | "-c" => count = true,
+                flag if flag.starts_with('-') && flag != "-" => return Err(UsageError::UnknownFlag(flag.to_string())),
+                path => {
+                    if input.is_some() {
+                        return Err(UsageError::ExtraInput(path.to_string()));
+                    }
+                    input = Some(path.to_string());
+                }
+            }
+        }
+
+        filter.pattern = match (substring, regex) {
+            (Some(..), Some(..)) => return Err(UsageError::TwoPatterns),
+            (Some(text), None) => Some(Pattern::substring(&text, ignore_case)),
+            (None, Some(pattern)) => Some(Pattern::regex(&pattern, ignore_case)?),
+            (None, None) => None,
+        };
+        if filter.invert && filter.pattern.is_none() {
+            return Err(UsageError::InvertWithoutPattern);
+        }
+        filter.check()?;
+
+        Ok(Options {
+            input: input.unwrap_or_else(|| String::from("error_handling/logs.txt")),
+            output,
+            filter,
+            count,
+        })
+    }
+}
+
+fn demo() -> Result<(), Error> {
     match divide(5.0, 0.0) {
         Ok(result_of_division) => {
             println!("{}", result_of_division);
@@ -42,32 +206,24 @@ fn main() -> Result<(), Error> { //Rust main can return this type
 
     match fs::read_to_string("error_handling/logs.txt") {
         Ok(text_that_was_read) => {
-            let error_logs = extract_errors(text_that_was_read.as_str());
-
-            match fs::write("error_handling/errors.txt", error_logs.join("\n")) {
-                Ok(..) => println!("Wrote errors.txt"),
-                Err(reason_write_failed) => {
-                    println!("Writing of errors.txt failed: {}", reason_write_failed);
-                }
-            }
+            println!("{} log lines", text_that_was_read.lines().count());
         }
         Err(why_this_failed) => {
             println!("{}", why_this_failed)
         }
     }
 
-    //Alternative to the nested matches above
+    //Alternative to the match above
     let text = fs::read_to_string("error_handling/logs.txt").expect("failed to read logs.txt");
-    let error_logs = extract_errors(text.as_str());
-    fs::write("errors.txt", error_logs.join("\n")).expect("failed to write errors.txt");
+    println!("{} log lines", text.lines().count());
 
     // The '?' operator on operations that can fail
     let text = fs::read_to_string("error_handling/errors.txt")?;
     println!("{}", text.len());
 
     Ok(())
-
 }
+
 fn string_test(
     a: String, // Stack and heap - used when we want ownership of text, use when we want to grow/shrink the data
     b: &String, // Stack and heap with a read only reference on the heap // Rarely used, Rust will automatically turn &String into &str
@@ -102,17 +258,27 @@ fn divide(a: f64, b: f64) -> Result<f64, Error> {
     }
 }
 
-fn extract_errors(text: &str) -> Vec<String> {
-    let split_text = text.split("\n");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut results = vec![];
+    fn options(args: &[&str]) -> Result<Options, UsageError> {
+        Options::from_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>())
+    }
 
-    for line in split_text {
-        if line.starts_with("ERROR") {
-            results.push(line.to_string());
-        }
+    #[test]
+    fn until_without_seconds_runs_to_the_end_of_the_minute() {
+        let until = options(&["--until", "14:50"]).unwrap().filter.until.unwrap();
+        assert_eq!(until.to_string(), "14:50:59");
+        let until = options(&["--until", "14:50:10"]).unwrap().filter.until.unwrap();
+        assert_eq!(until.to_string(), "14:50:10");
     }
 
-    results
+    #[test]
+    fn since_after_until_is_a_usage_error() {
+        let err = options(&["--since", "14:51", "--until", "14:50"]).unwrap_err();
+        assert!(matches!(err, UsageError::Filter(FilterError::EmptyWindow { .. })));
+        // The same minute is fine, --until 14:50 runs to 14:50:59
+        assert!(options(&["--since", "14:50:30", "--until", "14:50"]).is_ok());
+    }
 }
-